@@ -141,6 +141,15 @@ impl Chunk {
         self.write_chunk(OpCode::OpConstant(index), line);
     }
 
+    ///
+    /// Writes a value to the constants Values array without emitting an
+    /// instruction, returning its index for opcodes that take operands.
+    ///
+    pub fn make_constant(&mut self, value: Value) -> usize {
+        let (index, _) = self.constants.write_value(value);
+        index
+    }
+
     pub fn add_identifier_constant(
         &mut self,
         string_value: &RoxString,
@@ -207,6 +216,71 @@ impl Chunk {
             OpCode::OpLoop(offset) => {
                 Chunk::simple_instruction(format!("OP_LOOP {}", offset).as_str())
             }
+            OpCode::OpDup => Chunk::simple_instruction("OP_DUP"),
+            OpCode::OpVariant(constants_index, arity) => Chunk::simple_instruction(
+                format!("OP_VARIANT {} {}", constants_index, arity).as_str(),
+            ),
+            OpCode::OpMatchVariant(constants_index) => {
+                Chunk::constant_instruction("OP_MATCH_VARIANT", *constants_index, chunk)
+            }
+            OpCode::OpMatchRange(low, high) => {
+                Chunk::simple_instruction(format!("OP_MATCH_RANGE {} {}", low, high).as_str())
+            }
+            OpCode::OpBindPayload(arity) => {
+                Chunk::simple_instruction(format!("OP_BIND_PAYLOAD {}", arity).as_str())
+            }
+            OpCode::OpUnbind(count) => {
+                Chunk::simple_instruction(format!("OP_UNBIND {}", count).as_str())
+            }
+            OpCode::OpGetBinding(idx) => {
+                Chunk::simple_instruction(format!("OP_GET_BINDING {}", idx).as_str())
+            }
+            OpCode::OpMatchError => Chunk::simple_instruction("OP_MATCH_ERROR"),
+            OpCode::OpBuildList(count) => {
+                Chunk::simple_instruction(format!("OP_BUILD_LIST {}", count).as_str())
+            }
+            OpCode::OpBuildMap(count) => {
+                Chunk::simple_instruction(format!("OP_BUILD_MAP {}", count).as_str())
+            }
+            OpCode::OpBuildTuple(count) => {
+                Chunk::simple_instruction(format!("OP_BUILD_TUPLE {}", count).as_str())
+            }
+            OpCode::OpGetIndex => Chunk::simple_instruction("OP_GET_INDEX"),
+            OpCode::OpSetIndex => Chunk::simple_instruction("OP_SET_INDEX"),
+            OpCode::OpCheckLength(count, has_rest) => Chunk::simple_instruction(
+                format!("OP_CHECK_LENGTH {} {}", count, has_rest).as_str(),
+            ),
+            OpCode::OpListRest(start) => {
+                Chunk::simple_instruction(format!("OP_LIST_REST {}", start).as_str())
+            }
+            OpCode::OpCheckType(rox_type) => {
+                Chunk::simple_instruction(format!("OP_CHECK_TYPE {}", rox_type).as_str())
+            }
+            OpCode::OpGetProperty(constants_index) => {
+                Chunk::constant_instruction("OP_GET_PROPERTY", *constants_index, chunk)
+            }
+            OpCode::OpSetProperty(constants_index) => {
+                Chunk::constant_instruction("OP_SET_PROPERTY", *constants_index, chunk)
+            }
+            OpCode::OpInvoke(name_index, arg_count) => Chunk::constant_instruction(
+                format!("OP_INVOKE ({} args)", arg_count).as_str(),
+                *name_index,
+                chunk,
+            ),
+            OpCode::OpGetSlice => Chunk::simple_instruction("OP_GET_SLICE"),
+            OpCode::OpAssert => Chunk::simple_instruction("OP_ASSERT"),
+            OpCode::OpTest(name_index, offset) => Chunk::constant_instruction(
+                format!("OP_TEST (skip {})", offset.unwrap_or(0)).as_str(),
+                *name_index,
+                chunk,
+            ),
+            OpCode::OpEndTest => Chunk::simple_instruction("OP_END_TEST"),
+            OpCode::OpCall(arg_count) => {
+                Chunk::simple_instruction(format!("OP_CALL {}", arg_count).as_str())
+            }
+            OpCode::OpJumpIfNil(offset) => Chunk::simple_instruction(
+                format!("OP_JUMP_IF_NIL {}", offset.unwrap_or(0)).as_str(),
+            ),
         };
     }
 
@@ -1,8 +1,9 @@
-use crate::frontend::{Locals, LOCALS_COUNT};
+use crate::frontend::{EnumDeclaration, Enums, Locals, LOCALS_COUNT};
 use crate::opcode::VariableOp;
+use crate::types::GlobalTypes;
 use crate::{
-    Chunk, ObjectType, OpCode, Precedence, RoxNumber, RoxObject, RoxString, Scanner, Token,
-    TokenType, Value, DEBUG_MODE,
+    Chunk, ObjectType, OpCode, Precedence, RoxBigInt, RoxBytes, RoxEnumVariant, RoxNumber,
+    RoxObject, RoxString, RoxType, Scanner, Token, TokenType, Value, DEBUG_MODE,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -13,9 +14,30 @@ pub struct Compiler {
     chunk: Chunk,
     pub had_error: RefCell<bool>,
     pub panic_mode: RefCell<bool>,
+    pub warnings: RefCell<Vec<String>>,
 
     locals: Locals,
     scope_depth: usize,
+
+    enums: Enums,
+    match_bindings: Vec<Rc<RoxString>>,
+    loops: Vec<Loop>,
+
+    global_types: GlobalTypes,
+    last_type: RoxType,
+    // the types of the elements of a list or tuple literal, when
+    // the last expression compiled was one
+    element_types: Option<Vec<RoxType>>,
+    type_guards: bool,
+    trailing_value: bool,
+    errors: RefCell<Vec<String>>,
+}
+
+/// The bytecode for a unit of source, along with the warnings
+/// found while compiling it.
+pub struct Compiled {
+    pub chunk: Chunk,
+    pub warnings: Vec<String>,
 }
 
 enum ParseFn {
@@ -25,9 +47,35 @@ enum ParseFn {
     Unary,
     Literal,
     Grouping,
+    Match,
+    List,
+    Map,
+    Index,
+    Dot,
+    OptionalChain,
+    Call,
     Variable(Rc<RoxString>, usize),
     String(Rc<RoxString>, usize),
     Number(RoxNumber, usize),
+    BigInt(Rc<RoxBigInt>, usize),
+    Bytes(Rc<RoxBytes>, usize),
+}
+
+/// A loop being compiled, which `break` and `continue` statements
+/// inside it may target, either as the innermost loop or by label.
+struct Loop {
+    label: Option<Rc<RoxString>>,
+    // where `continue` loops back to, unless it comes after the body
+    continue_start: Option<usize>,
+    // the number of locals declared outside of the loop body
+    locals: usize,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+enum MatchPattern {
+    Variant(EnumDeclaration, Rc<RoxString>, Vec<Rc<RoxString>>),
+    Value,
 }
 
 struct ParseRule {
@@ -44,8 +92,18 @@ impl Compiler {
             token_idx: 0,
             had_error: RefCell::new(false),
             panic_mode: RefCell::new(false),
+            warnings: RefCell::new(vec![]),
             scope_depth: 0,
             locals: Locals::new(),
+            enums: Enums::new(),
+            match_bindings: vec![],
+            loops: vec![],
+            global_types: GlobalTypes::new(),
+            last_type: RoxType::Any,
+            element_types: None,
+            type_guards: false,
+            trailing_value: false,
+            errors: RefCell::new(vec![]),
         }
     }
 
@@ -57,9 +115,18 @@ impl Compiler {
             ParseFn::Unary => self.unary(can_assign),
             ParseFn::Literal => self.literal(can_assign),
             ParseFn::Grouping => self.grouping(can_assign),
+            ParseFn::Match => self.match_expression(can_assign),
+            ParseFn::List => self.list(can_assign),
+            ParseFn::Map => self.map(can_assign),
+            ParseFn::Index => self.index(can_assign),
+            ParseFn::Dot => self.dot(can_assign),
+            ParseFn::Call => self.call(can_assign),
+            ParseFn::OptionalChain => self.optional_chain(can_assign),
             ParseFn::Variable(str, line) => self.variable(&str, line, can_assign),
             ParseFn::String(str, line) => self.string(&str, line, can_assign),
             ParseFn::Number(num, line) => self.number(num, line, can_assign),
+            ParseFn::BigInt(int, line) => self.big_int(&int, line, can_assign),
+            ParseFn::Bytes(bytes, line) => self.bytes(&bytes, line, can_assign),
         }
 
         Ok(())
@@ -105,6 +172,16 @@ impl Compiler {
                 prefix_fn: Some(ParseFn::Number(*num, line)),
                 infix_fn: None,
             },
+            TokenType::BigInt(int) => ParseRule {
+                precedence: Precedence::PrecNone,
+                prefix_fn: Some(ParseFn::BigInt(Rc::clone(int), line)),
+                infix_fn: None,
+            },
+            TokenType::Bytes(bytes) => ParseRule {
+                precedence: Precedence::PrecNone,
+                prefix_fn: Some(ParseFn::Bytes(Rc::clone(bytes), line)),
+                infix_fn: None,
+            },
             TokenType::True => ParseRule {
                 precedence: Precedence::PrecNone,
                 prefix_fn: Some(ParseFn::Literal),
@@ -156,9 +233,9 @@ impl Compiler {
                 infix_fn: Some(ParseFn::Binary),
             },
             TokenType::LeftParen => ParseRule {
-                precedence: Precedence::PrecNone,
+                precedence: Precedence::PrecCall,
                 prefix_fn: Some(ParseFn::Grouping),
-                infix_fn: None,
+                infix_fn: Some(ParseFn::Call),
             },
             TokenType::RightParen => ParseRule {
                 precedence: Precedence::PrecNone,
@@ -170,6 +247,42 @@ impl Compiler {
                 prefix_fn: None,
                 infix_fn: None,
             },
+            TokenType::LeftBracket => ParseRule {
+                precedence: Precedence::PrecCall,
+                prefix_fn: Some(ParseFn::List),
+                infix_fn: Some(ParseFn::Index),
+            },
+            TokenType::Dot => ParseRule {
+                precedence: Precedence::PrecCall,
+                prefix_fn: None,
+                infix_fn: Some(ParseFn::Dot),
+            },
+            TokenType::QuestionDot | TokenType::QuestionLeftBracket => ParseRule {
+                precedence: Precedence::PrecCall,
+                prefix_fn: None,
+                infix_fn: Some(ParseFn::OptionalChain),
+            },
+            TokenType::LeftBrace => ParseRule {
+                precedence: Precedence::PrecNone,
+                prefix_fn: Some(ParseFn::Map),
+                infix_fn: None,
+            },
+            TokenType::Comma
+            | TokenType::Colon
+            | TokenType::DotDot
+            | TokenType::DotDotDot
+            | TokenType::FatArrow
+            | TokenType::RightBracket
+            | TokenType::RightBrace => ParseRule {
+                precedence: Precedence::PrecNone,
+                prefix_fn: None,
+                infix_fn: None,
+            },
+            TokenType::Match => ParseRule {
+                precedence: Precedence::PrecNone,
+                prefix_fn: Some(ParseFn::Match),
+                infix_fn: None,
+            },
 
             /*
                         TokenType::Var => ParseRule {
@@ -193,7 +306,7 @@ impl Compiler {
                 prefix_fn: Some(ParseFn::String(str.clone(), line)),
                 infix_fn: None,
             },
-            TokenType::EOF => ParseRule {
+            TokenType::EOF | TokenType::Error(_) => ParseRule {
                 precedence: Precedence::PrecNone,
                 prefix_fn: None,
                 infix_fn: None,
@@ -268,13 +381,25 @@ impl Compiler {
 
         (*self.panic_mode.borrow_mut()) = true;
 
-        eprintln!(
+        // errors are returned from compilation for the caller to show
+        let error = format!(
             "Error at [{}, {}] with message: {}",
             token.line, token.column, message
         );
+        self.errors.borrow_mut().push(error);
         (*self.had_error.borrow_mut()) = true;
     }
 
+    /// Records a diagnostic that does not stop compilation. Warnings
+    /// are handed back with the compiled chunk for the caller to show.
+    fn warning_at(&self, token: &Token, message: &str) {
+        let warning = format!(
+            "Warning at [{}, {}] with message: {}",
+            token.line, token.column, message
+        );
+        self.warnings.borrow_mut().push(warning);
+    }
+
     fn synchronize(&mut self) {
         (*self.panic_mode.borrow_mut()) = false;
         let mut current_token_type = &self.peek().token_type;
@@ -286,12 +411,15 @@ impl Compiler {
 
             match current_token_type {
                 TokenType::Class
+                | TokenType::Enum
                 | TokenType::Fun
                 | TokenType::Var
                 | TokenType::For
                 | TokenType::If
                 | TokenType::While
+                | TokenType::Do
                 | TokenType::Print
+                | TokenType::Assert
                 | TokenType::Return => return,
                 _ => (),
             }
@@ -309,6 +437,13 @@ impl Compiler {
     fn declaration(&mut self) {
         if self.match_token(TokenType::Var) {
             self.var_declaration();
+        } else if self.match_token(TokenType::Enum) {
+            self.enum_declaration();
+        } else if self.match_token(TokenType::Fun) {
+            self.fun_declaration();
+        } else if self.is_test_block() {
+            self.advance();
+            self.test_block();
         } else {
             self.statement();
         }
@@ -318,10 +453,21 @@ impl Compiler {
     }
 
     fn var_declaration(&mut self) {
+        if self.match_token(TokenType::LeftBracket) {
+            self.destructuring_declaration(TokenType::RightBracket);
+            return;
+        } else if self.match_token(TokenType::LeftBrace) {
+            self.destructuring_declaration(TokenType::RightBrace);
+            return;
+        }
+
         let index = self.parse_variable("Expect variable name.");
+        let name = self.previous().clone();
+        let annotation = self.type_annotation();
 
         if self.match_token(TokenType::Equal) {
-            self.expression();
+            let inferred = self.typed_expression();
+            self.check_annotation(&name, annotation, inferred);
         } else {
             self.emit_byte(OpCode::OpNil);
         }
@@ -331,27 +477,387 @@ impl Compiler {
             "Expect ';' after variable declaration.",
         );
 
+        if let TokenType::Identifier(id) = &name.token_type {
+            if self.scope_depth > 0 {
+                self.locals.annotate_variable(annotation);
+            } else {
+                self.global_types.annotate(id, annotation);
+            }
+        }
+
         self.define_variable(index);
     }
 
+    /// Rox has no user functions yet, so a `fun` declaration is
+    /// reported and skipped, along with any parameter and return
+    /// annotations in its signature, as in `fun f(a: String) -> Bool`.
+    fn fun_declaration(&mut self) {
+        self.error(
+            "Functions are not supported yet, so parameters and return values cannot be annotated.",
+        );
+
+        while !self.check_token(TokenType::LeftBrace) && !self.check_token(TokenType::EOF) {
+            self.advance();
+        }
+
+        let mut depth = 0;
+        while !self.check_token(TokenType::EOF) {
+            self.advance();
+            match self.previous().token_type {
+                TokenType::LeftBrace => depth += 1,
+                TokenType::RightBrace => depth -= 1,
+                _ => (),
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+
+        // the whole declaration was skipped, so the next one can be
+        // compiled without synchronizing
+        *self.panic_mode.borrow_mut() = false;
+    }
+
+    /// Parses an optional `: Type` annotation after a variable name.
+    fn type_annotation(&mut self) -> RoxType {
+        if !self.match_token(TokenType::Colon) {
+            return RoxType::Any;
+        }
+
+        let type_name = match self.consume_identifier("Expect type name after ':'.") {
+            Some(type_name) => type_name,
+            None => return RoxType::Any,
+        };
+
+        RoxType::from_name(&type_name).unwrap_or_else(|| {
+            self.error(&format!("Unknown type '{}'.", type_name));
+            RoxType::Any
+        })
+    }
+
+    /// Compiles an expression and returns the type inferred for it,
+    /// which is Any when the compiler cannot tell. The inference only
+    /// covers this expression, so nothing compiled before it leaks in.
+    fn typed_expression(&mut self) -> RoxType {
+        self.last_type = RoxType::Any;
+        self.expression();
+        std::mem::replace(&mut self.last_type, RoxType::Any)
+    }
+
+    /// Compiles an expression and returns the types inferred for its
+    /// elements when it is a list or tuple literal. Other expressions
+    /// give no types, so their elements are unknown.
+    fn typed_elements(&mut self) -> Vec<RoxType> {
+        self.typed_expression();
+        self.element_types.take().unwrap_or_default()
+    }
+
+    /// Reports a mismatch between an annotated variable and the type
+    /// inferred for the value stored into it, and guards the value at
+    /// runtime when type guards are enabled.
+    fn check_annotation(&mut self, name: &Token, annotation: RoxType, inferred: RoxType) {
+        if annotation == RoxType::Any {
+            return;
+        }
+
+        if !annotation.accepts(&inferred) {
+            let name = match &name.token_type {
+                TokenType::Identifier(id) => id.to_string(),
+                _ => String::new(),
+            };
+            self.error(&format!(
+                "Type mismatch: '{}' is declared {} but assigned {}.",
+                name, annotation, inferred
+            ));
+        }
+
+        if self.type_guards {
+            self.emit_byte(OpCode::OpCheckType(annotation));
+        }
+    }
+
+    fn enum_declaration(&mut self) {
+        let name = match self.consume_identifier("Expect enum name.") {
+            Some(name) => name,
+            None => return,
+        };
+        let mut declaration = EnumDeclaration::new(&name);
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before enum body.");
+
+        while !self.check_token(TokenType::RightBrace) && !self.check_token(TokenType::EOF) {
+            let variant = match self.consume_identifier("Expect variant name.") {
+                Some(variant) => variant,
+                None => return,
+            };
+
+            if declaration.has_variant(&variant) {
+                self.error("Already a variant with this name in enum.");
+            }
+
+            // optional payload field names, e.g. Blue(r)
+            let mut arity = 0;
+            if self.match_token(TokenType::LeftParen) {
+                if !self.check_token(TokenType::RightParen) {
+                    loop {
+                        self.consume_identifier("Expect payload field name.");
+                        arity += 1;
+                        if !self.match_token(TokenType::Comma) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expect ')' after variant payload.");
+            }
+
+            declaration.add_variant(&variant, arity);
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after enum body.");
+
+        if declaration.variants.is_empty() {
+            self.error("Enum must declare at least one variant.");
+        }
+
+        self.enums.declare(declaration);
+    }
+
+    /// Compiles `var [a, b, ...rest] = xs;` and `var {a, b} = record;` by
+    /// reading each target out of the source value with index operations.
+    /// Inside a scope the source value is kept in a hidden local slot
+    /// beneath the targets; at global scope it is popped once all targets
+    /// are defined.
+    fn destructuring_declaration(&mut self, closing: TokenType) {
+        let is_list = closing == TokenType::RightBracket;
+        let (targets, rest) = match self.destructuring_targets(closing, is_list) {
+            Some(pattern) => pattern,
+            None => return,
+        };
+
+        self.consume(TokenType::Equal, "Expect '=' after destructuring pattern.");
+
+        let source_slot = if self.scope_depth > 0 {
+            let hidden = Token::new(
+                TokenType::Identifier(Rc::new(RoxString::new(""))),
+                self.previous().line,
+                self.previous().column,
+            );
+            self.add_local(&hidden);
+            Some(self.locals.size() - 1)
+        } else {
+            None
+        };
+
+        self.expression();
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after variable declaration.",
+        );
+
+        if is_list {
+            self.emit_byte(OpCode::OpCheckLength(targets.len(), rest.is_some()));
+        }
+
+        for (idx, target) in targets.iter().enumerate() {
+            self.emit_destructuring_source(source_slot);
+            self.emit_destructuring_key(target, idx, is_list);
+            self.emit_byte(OpCode::OpGetIndex);
+            self.define_target(target);
+        }
+
+        if let Some(rest) = rest {
+            self.emit_destructuring_source(source_slot);
+            self.emit_byte(OpCode::OpListRest(targets.len()));
+            self.define_target(&rest);
+        }
+
+        if source_slot.is_none() {
+            self.emit_byte(OpCode::OpPop);
+        }
+    }
+
+    /// Compiles the swap-style `[a, b] = [b, a];` assignment to
+    /// existing variables.
+    fn destructuring_assignment(&mut self) {
+        self.consume(
+            TokenType::LeftBracket,
+            "Expect '[' before assignment targets.",
+        );
+        let (targets, rest) = match self.destructuring_targets(TokenType::RightBracket, true) {
+            Some(pattern) => pattern,
+            None => return,
+        };
+
+        self.consume(TokenType::Equal, "Expect '=' after destructuring pattern.");
+        let element_types = self.typed_elements();
+        self.consume(
+            TokenType::Semicolon,
+            "Expected ';' after expression statement.",
+        );
+
+        self.emit_byte(OpCode::OpCheckLength(targets.len(), rest.is_some()));
+
+        for (idx, target) in targets.iter().enumerate() {
+            self.emit_byte(OpCode::OpDup);
+            self.emit_destructuring_key(target, idx, true);
+            self.emit_byte(OpCode::OpGetIndex);
+            let inferred = element_types.get(idx).copied().unwrap_or_default();
+            self.assign_target(target, inferred);
+            self.emit_byte(OpCode::OpPop);
+        }
+
+        if let Some(rest) = rest {
+            self.emit_byte(OpCode::OpDup);
+            self.emit_byte(OpCode::OpListRest(targets.len()));
+            self.assign_target(&rest, RoxType::List);
+            self.emit_byte(OpCode::OpPop);
+        }
+
+        self.emit_byte(OpCode::OpPop);
+    }
+
+    /// Parses the identifiers of a destructuring pattern up to and including
+    /// the closing token, along with an optional trailing `...rest` target.
+    fn destructuring_targets(
+        &mut self,
+        closing: TokenType,
+        allow_rest: bool,
+    ) -> Option<(Vec<Token>, Option<Token>)> {
+        let mut targets = vec![];
+        let mut rest = None;
+
+        while !self.check_token(closing.clone()) && !self.check_token(TokenType::EOF) {
+            if allow_rest && self.match_token(TokenType::DotDotDot) {
+                self.consume_identifier("Expect variable name after '...'.")?;
+                rest = Some(self.previous().clone());
+                break;
+            }
+
+            self.consume_identifier("Expect variable name in destructuring pattern.")?;
+            targets.push(self.previous().clone());
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(closing, "Expect end of destructuring pattern.");
+
+        Some((targets, rest))
+    }
+
+    /// Checks whether the statement at the cursor is a bracketed
+    /// destructuring assignment rather than a list expression.
+    fn is_destructuring_assignment(&self) -> bool {
+        if !self.check_token(TokenType::LeftBracket) {
+            return false;
+        }
+
+        let mut depth = 0;
+        for (offset, token) in self.tokens[self.token_idx..].iter().enumerate() {
+            match token.token_type {
+                TokenType::LeftBracket => depth += 1,
+                TokenType::RightBracket => depth -= 1,
+                TokenType::Semicolon | TokenType::EOF => return false,
+                _ => (),
+            }
+
+            if depth == 0 {
+                return self
+                    .tokens
+                    .get(self.token_idx + offset + 1)
+                    .is_some_and(|next| next.token_type == TokenType::Equal);
+            }
+        }
+
+        false
+    }
+
+    fn emit_destructuring_source(&mut self, source_slot: Option<usize>) {
+        match source_slot {
+            Some(slot) => self.emit_byte(OpCode::OpGetLocal(slot)),
+            None => self.emit_byte(OpCode::OpDup),
+        }
+    }
+
+    fn emit_destructuring_key(&mut self, target: &Token, idx: usize, is_list: bool) {
+        if is_list {
+            self.emit_constant(Value::Number(RoxNumber(idx as f64)), target.line);
+        } else if let TokenType::Identifier(name) = &target.token_type {
+            self.string(name, target.line, false);
+        }
+    }
+
+    /// Declares and defines a destructuring target with the
+    /// value on top of the stack.
+    fn define_target(&mut self, target: &Token) {
+        let name = match &target.token_type {
+            TokenType::Identifier(name) => Rc::clone(name),
+            _ => return,
+        };
+
+        self.declare_token(target);
+
+        // patterns have no annotations, so a global declared again
+        // by one loses its old annotation like any other declaration
+        let index = if self.scope_depth > 0 {
+            0
+        } else {
+            self.global_types.annotate(&name, RoxType::Any);
+            self.emit_identifier_constant(&name, target.line, VariableOp::Define)
+        };
+        self.define_variable(index);
+    }
+
+    /// Assigns the value on top of the stack, whose type was inferred
+    /// as inferred, to an existing variable, checking it against the
+    /// variable's annotation and leaving the value on the stack.
+    fn assign_target(&mut self, target: &Token, inferred: RoxType) {
+        let name = match &target.token_type {
+            TokenType::Identifier(name) => Rc::clone(name),
+            _ => return,
+        };
+
+        match self.locals.resolve_local(&name) {
+            (_, Some(local_idx)) => {
+                let annotation = self.locals.annotation(local_idx);
+                self.check_annotation(target, annotation, inferred);
+                self.emit_byte(OpCode::OpSetLocal(local_idx));
+            }
+            (_, None) => {
+                let annotation = self.global_types.resolve(&name);
+                self.check_annotation(target, annotation, inferred);
+                self.chunk
+                    .add_identifier_constant(&name, target.line, VariableOp::SetGlobal);
+            }
+        }
+    }
+
     fn declare_variable(&mut self) {
+        let token = self.previous().clone();
+        self.declare_token(&token);
+    }
+
+    fn declare_token(&mut self, token: &Token) {
         // for globals
         if self.scope_depth == 0 {
             return;
         }
 
-        let token = self.previous().clone();
-
         let is_doubly_declared = self
             .locals
-            .local_is_doubly_declared(&token, self.scope_depth);
+            .local_is_doubly_declared(token, self.scope_depth);
 
         if is_doubly_declared {
             self.error("Already a variable with this name in scope.");
             return;
         }
 
-        self.add_local(&token);
+        self.add_local(token);
     }
 
     fn add_local(&mut self, token: &Token) {
@@ -377,21 +883,94 @@ impl Compiler {
     fn statement(&mut self) {
         if self.match_token(TokenType::Print) {
             self.print_statement();
+        } else if self.match_token(TokenType::Assert) {
+            self.assert_statement();
         } else if self.match_token(TokenType::For) {
-            self.for_statement();
+            self.for_statement(None);
         } else if self.match_token(TokenType::If) {
             self.if_statement();
         } else if self.match_token(TokenType::While) {
-            self.while_statement();
+            self.while_statement(None);
+        } else if self.match_token(TokenType::Do) {
+            self.do_while_statement(None);
+        } else if self.match_token(TokenType::Break) {
+            self.break_statement();
+        } else if self.match_token(TokenType::Continue) {
+            self.continue_statement();
+        } else if self.is_labeled_loop() {
+            self.labeled_loop();
         } else if self.match_token(TokenType::LeftBrace) {
             self.begin_scope();
             self.block();
             self.end_scope();
+        } else if self.is_destructuring_assignment() {
+            self.destructuring_assignment();
         } else {
             self.expression_statement();
         }
     }
 
+    fn assert_statement(&mut self) {
+        self.expression();
+
+        if self.match_token(TokenType::Comma) {
+            self.expression();
+        } else {
+            self.emit_byte(OpCode::OpNil);
+        }
+
+        self.consume(TokenType::Semicolon, "Expect ';' after assertion.");
+        self.emit_byte(OpCode::OpAssert);
+    }
+
+    /// `test` is only a keyword when it is followed by the test's
+    /// name, so it can still be used as a variable name.
+    fn is_test_block(&self) -> bool {
+        let is_test = matches!(
+            &self.peek().token_type,
+            TokenType::Identifier(name) if &***name == "test"
+        );
+        let next = self.tokens.get(self.token_idx + 1);
+
+        is_test
+            && matches!(
+                next,
+                Some(Token {
+                    token_type: TokenType::StringLiteral(_),
+                    ..
+                })
+            )
+    }
+
+    /// Compiles `test "name" { ... }`. The body is skipped unless
+    /// the VM is running in test mode.
+    fn test_block(&mut self) {
+        let name = match &self.advance().token_type {
+            TokenType::StringLiteral(name) => Rc::clone(name),
+            _ => unreachable!("Test block name was checked by is_test_block"),
+        };
+
+        if self.scope_depth > 0 {
+            self.error("Test blocks must be declared at the top level.");
+            return;
+        }
+
+        let name_index =
+            self.chunk
+                .make_constant(Value::Object(RoxObject::new(ObjectType::ObjString(
+                    RoxString::new(&name),
+                ))));
+        let test_jump = self.emit_jump(OpCode::OpTest(name_index, None));
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before test body.");
+        self.begin_scope();
+        self.block();
+        self.end_scope();
+        self.emit_byte(OpCode::OpEndTest);
+
+        self.patch_jump(test_jump, OpCode::OpTest(name_index, None));
+    }
+
     fn print_statement(&mut self) {
         self.expression();
         self.consume(TokenType::Semicolon, "Expected ';' after value.");
@@ -400,6 +979,20 @@ impl Compiler {
 
     fn expression_statement(&mut self) {
         self.expression();
+
+        // code compiled for eval leaves the value of its final
+        // expression on the stack as its result
+        if self.trailing_value && self.scope_depth == 0 {
+            let terminated = self.match_token(TokenType::Semicolon);
+            if self.check_token(TokenType::EOF) {
+                return;
+            }
+            if terminated {
+                self.emit_byte(OpCode::OpPop);
+                return;
+            }
+        }
+
         self.consume(
             TokenType::Semicolon,
             "Expected ';' after expression statement.",
@@ -407,7 +1000,130 @@ impl Compiler {
         self.emit_byte(OpCode::OpPop);
     }
 
-    fn for_statement(&mut self) {
+    /// Checks for a label such as `outer:` before a statement.
+    fn is_labeled_loop(&self) -> bool {
+        let next = self.tokens.get(self.token_idx + 1);
+
+        matches!(self.peek().token_type, TokenType::Identifier(_))
+            && matches!(
+                next,
+                Some(Token {
+                    token_type: TokenType::Colon,
+                    ..
+                })
+            )
+    }
+
+    fn labeled_loop(&mut self) {
+        let label = self.consume_identifier("Expect loop label.");
+        if let Some(name) = &label {
+            if self
+                .loops
+                .iter()
+                .any(|outer| outer.label.as_ref() == Some(name))
+            {
+                self.error(&format!(
+                    "Label '{}' is already used by an enclosing loop.",
+                    name
+                ));
+            }
+        }
+        self.consume(TokenType::Colon, "Expect ':' after loop label.");
+
+        if self.match_token(TokenType::For) {
+            self.for_statement(label);
+        } else if self.match_token(TokenType::While) {
+            self.while_statement(label);
+        } else if self.match_token(TokenType::Do) {
+            self.do_while_statement(label);
+        } else {
+            self.error("Expect a loop after a label.");
+        }
+    }
+
+    fn begin_loop(&mut self, label: Option<Rc<RoxString>>, continue_start: Option<usize>) {
+        self.loops.push(Loop {
+            label,
+            continue_start,
+            locals: self.locals.size(),
+            breaks: vec![],
+            continues: vec![],
+        });
+    }
+
+    /// Ends the innermost loop, sending its `break` statements here.
+    fn end_loop(&mut self) {
+        let current_loop = self
+            .loops
+            .pop()
+            .expect("Error ending loop with no loop open");
+        for jump in current_loop.breaks {
+            self.patch_jump(jump, OpCode::OpJump(None));
+        }
+    }
+
+    /// Finds the loop targeted by a `break` or `continue` statement,
+    /// which is the innermost loop unless a label is given.
+    fn jump_target(&mut self, keyword: &str) -> Option<usize> {
+        let target = if self.check_token(TokenType::Identifier(Rc::new(RoxString::new("")))) {
+            let label = self.consume_identifier("Expect loop label.")?;
+            let target = self
+                .loops
+                .iter()
+                .rposition(|enclosing| enclosing.label.as_ref() == Some(&label));
+            if target.is_none() {
+                self.error(&format!("No enclosing loop is labeled '{}'.", label));
+            }
+            target
+        } else {
+            let target = self.loops.len().checked_sub(1);
+            if target.is_none() {
+                self.error(&format!("Can't use '{}' outside of a loop.", keyword));
+            }
+            target
+        };
+
+        self.consume(
+            TokenType::Semicolon,
+            &format!("Expect ';' after '{}'.", keyword),
+        );
+        target
+    }
+
+    /// Pops the locals declared inside a loop that is being left,
+    /// without ending their scopes in the compiler.
+    fn unwind_loop_locals(&mut self, target: usize) {
+        for _ in self.loops[target].locals..self.locals.size() {
+            self.emit_byte(OpCode::OpPop);
+        }
+    }
+
+    fn break_statement(&mut self) {
+        let Some(target) = self.jump_target("break") else {
+            return;
+        };
+
+        self.unwind_loop_locals(target);
+        let jump = self.emit_jump(OpCode::OpJump(None));
+        self.loops[target].breaks.push(jump);
+    }
+
+    fn continue_statement(&mut self) {
+        let Some(target) = self.jump_target("continue") else {
+            return;
+        };
+
+        self.unwind_loop_locals(target);
+        match self.loops[target].continue_start {
+            Some(start) => self.emit_loop(start),
+            None => {
+                let jump = self.emit_jump(OpCode::OpJump(None));
+                self.loops[target].continues.push(jump);
+            }
+        }
+    }
+
+    fn for_statement(&mut self, label: Option<Rc<RoxString>>) {
         self.begin_scope();
         self.consume(TokenType::LeftParen, "Expect '(' after 'for'.");
 
@@ -438,8 +1154,7 @@ impl Compiler {
             let incr_start = self.chunk.count();
 
             self.expression();
-            // TODO => Why does this fix the tests?
-            //self.emit_byte(OpCode::OpPop);
+            self.emit_byte(OpCode::OpPop);
             self.consume(TokenType::RightParen, "Expect ')' after for clauses.");
 
             self.emit_loop(loop_start);
@@ -447,6 +1162,9 @@ impl Compiler {
             self.patch_jump(body_jump, OpCode::OpJump(None));
         }
 
+        // continuing runs the increment, and breaking leaves
+        // the loop variables to be popped by end_scope
+        self.begin_loop(label, Some(loop_start));
         self.statement();
         self.emit_loop(loop_start);
 
@@ -455,11 +1173,12 @@ impl Compiler {
             self.patch_jump(exit_jump_offset, OpCode::OpJumpIfFalse(None));
             self.emit_byte(OpCode::OpPop);
         }
+        self.end_loop();
 
         self.end_scope();
     }
 
-    fn while_statement(&mut self) {
+    fn while_statement(&mut self, label: Option<Rc<RoxString>>) {
         let loop_start = self.chunk.count();
 
         self.consume(TokenType::LeftParen, "Expect '(' after 'while'.");
@@ -468,11 +1187,48 @@ impl Compiler {
 
         let exit_jump = self.emit_jump(OpCode::OpJumpIfFalse(None));
         self.emit_jump(OpCode::OpPop);
+        self.begin_loop(label, Some(loop_start));
         self.statement();
         self.emit_loop(loop_start);
 
         self.patch_jump(exit_jump, OpCode::OpJumpIfFalse(None));
         self.emit_byte(OpCode::OpPop);
+        self.end_loop();
+    }
+
+    /// Compiles `do statement while (condition);`, which runs
+    /// the body once before checking the condition.
+    fn do_while_statement(&mut self, label: Option<Rc<RoxString>>) {
+        let loop_start = self.chunk.count();
+
+        self.begin_loop(label, None);
+        self.statement();
+
+        // the condition comes after the body, so continue jumps forward
+        let continues = std::mem::take(
+            &mut self
+                .loops
+                .last_mut()
+                .expect("Error finding do loop")
+                .continues,
+        );
+        for jump in continues {
+            self.patch_jump(jump, OpCode::OpJump(None));
+        }
+
+        self.consume(TokenType::While, "Expect 'while' after do loop body.");
+        self.consume(TokenType::LeftParen, "Expect '(' after 'while'.");
+        self.expression();
+        self.consume(TokenType::RightParen, "Expect ')' after condition.");
+        self.consume(TokenType::Semicolon, "Expect ';' after do loop condition.");
+
+        let exit_jump = self.emit_jump(OpCode::OpJumpIfFalse(None));
+        self.emit_byte(OpCode::OpPop);
+        self.emit_loop(loop_start);
+
+        self.patch_jump(exit_jump, OpCode::OpJumpIfFalse(None));
+        self.emit_byte(OpCode::OpPop);
+        self.end_loop();
     }
 
     fn if_statement(&mut self) {
@@ -507,6 +1263,10 @@ impl Compiler {
         match opcode {
             OpCode::OpJumpIfFalse(_) => self.chunk.code[offset] = OpCode::OpJumpIfFalse(Some(jump)),
             OpCode::OpJump(_) => self.chunk.code[offset] = OpCode::OpJump(Some(jump)),
+            OpCode::OpJumpIfNil(_) => self.chunk.code[offset] = OpCode::OpJumpIfNil(Some(jump)),
+            OpCode::OpTest(name_index, _) => {
+                self.chunk.code[offset] = OpCode::OpTest(name_index, Some(jump))
+            }
             _ => (),
         }
     }
@@ -541,6 +1301,7 @@ impl Compiler {
         self.parse(&Precedence::PrecAnd);
 
         self.patch_jump(end_jump, OpCode::OpJumpIfFalse(None));
+        self.last_type = RoxType::Any;
     }
 
     fn or(&mut self, _can_assign: bool) {
@@ -552,10 +1313,24 @@ impl Compiler {
 
         self.parse(&Precedence::PrecOr);
         self.patch_jump(end_jump, OpCode::OpJump(None));
+        self.last_type = RoxType::Any;
     }
 
     fn number(&mut self, num: RoxNumber, line: usize, _can_assign: bool) {
         self.emit_constant(Value::Number(num), line);
+        self.last_type = RoxType::Number;
+    }
+
+    fn big_int(&mut self, int: &RoxBigInt, line: usize, _can_assign: bool) {
+        let int = RoxObject::new(ObjectType::ObjBigInt(int.clone()));
+        self.emit_constant(Value::Object(int), line);
+        self.last_type = RoxType::BigInt;
+    }
+
+    fn bytes(&mut self, bytes: &RoxBytes, line: usize, _can_assign: bool) {
+        let bytes = RoxObject::new(ObjectType::ObjBytes(bytes.clone()));
+        self.emit_constant(Value::Object(bytes), line);
+        self.last_type = RoxType::Bytes;
     }
 
     /// Writes a constant value to the chunk, bypassing
@@ -576,41 +1351,517 @@ impl Compiler {
             .add_identifier_constant(string_value, line, variable_op)
     }
 
+    /// Compiles a parenthesized expression, or a tuple when the
+    /// parentheses are empty or the first element is followed by a
+    /// comma, as in `()`, `(a,)` and `(a, b)`.
     fn grouping(&mut self, _can_assign: bool) {
+        if self.match_token(TokenType::RightParen) {
+            self.emit_byte(OpCode::OpBuildTuple(0));
+            self.last_type = RoxType::Tuple;
+            return;
+        }
+
         self.expression();
-        self.consume(TokenType::RightParen, "Expect ')' after expression.");
+        if !self.match_token(TokenType::Comma) {
+            self.consume(TokenType::RightParen, "Expect ')' after expression.");
+            return;
+        }
+
+        let mut element_types = vec![self.last_type];
+        while !self.check_token(TokenType::RightParen) && !self.check_token(TokenType::EOF) {
+            element_types.push(self.typed_expression());
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightParen, "Expect ')' after tuple elements.");
+        self.emit_byte(OpCode::OpBuildTuple(element_types.len()));
+        self.last_type = RoxType::Tuple;
+        self.element_types = Some(element_types);
+    }
+
+    fn list(&mut self, _can_assign: bool) {
+        let mut element_types = vec![];
+
+        while !self.check_token(TokenType::RightBracket) && !self.check_token(TokenType::EOF) {
+            element_types.push(self.typed_expression());
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBracket, "Expect ']' after list elements.");
+        self.emit_byte(OpCode::OpBuildList(element_types.len()));
+        self.last_type = RoxType::List;
+        self.element_types = Some(element_types);
+    }
+
+    fn map(&mut self, _can_assign: bool) {
+        let mut count = 0;
+
+        while !self.check_token(TokenType::RightBrace) && !self.check_token(TokenType::EOF) {
+            // keys are either bare identifiers or string literals
+            let key = self.advance().clone();
+            match &key.token_type {
+                TokenType::Identifier(name) | TokenType::StringLiteral(name) => {
+                    self.string(name, key.line, false)
+                }
+                _ => {
+                    self.error("Expect identifier or string as map key.");
+                    return;
+                }
+            }
+
+            self.consume(TokenType::Colon, "Expect ':' after map key.");
+            self.expression();
+            count += 1;
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after map entries.");
+        self.emit_byte(OpCode::OpBuildMap(count));
+        self.last_type = RoxType::Map;
+    }
+
+    fn index(&mut self, can_assign: bool) {
+        // a missing slice bound is compiled as nil, as in `s[..3]`
+        if self.check_token(TokenType::DotDot) {
+            self.emit_byte(OpCode::OpNil);
+        } else {
+            self.expression();
+        }
+
+        if self.match_token(TokenType::DotDot) {
+            if self.check_token(TokenType::RightBracket) {
+                self.emit_byte(OpCode::OpNil);
+            } else {
+                self.expression();
+            }
+            self.consume(TokenType::RightBracket, "Expect ']' after slice.");
+            self.emit_byte(OpCode::OpGetSlice);
+            self.last_type = RoxType::Any;
+            return;
+        }
+
+        self.consume(TokenType::RightBracket, "Expect ']' after index.");
+
+        if can_assign && self.match_token(TokenType::Equal) {
+            self.expression();
+            self.emit_byte(OpCode::OpSetIndex);
+        } else {
+            self.emit_byte(OpCode::OpGetIndex);
+            self.last_type = RoxType::Any;
+        }
+    }
+
+    fn call(&mut self, _can_assign: bool) {
+        let arg_count = self.argument_list();
+        self.emit_byte(OpCode::OpCall(arg_count));
+        self.last_type = RoxType::Any;
+    }
+
+    fn argument_list(&mut self) -> usize {
+        let mut arg_count = 0;
+
+        while !self.check_token(TokenType::RightParen) && !self.check_token(TokenType::EOF) {
+            self.expression();
+            arg_count += 1;
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightParen, "Expect ')' after arguments.");
+        arg_count
+    }
+
+    fn dot(&mut self, can_assign: bool) {
+        let name = match self.consume_identifier("Expect property name after '.'.") {
+            Some(name) => name,
+            None => return,
+        };
+        let name_index =
+            self.chunk
+                .make_constant(Value::Object(RoxObject::new(ObjectType::ObjString(
+                    RoxString::new(&name),
+                ))));
+
+        if can_assign && self.match_token(TokenType::Equal) {
+            self.expression();
+            self.emit_byte(OpCode::OpSetProperty(name_index));
+        } else if self.match_token(TokenType::LeftParen) {
+            let arg_count = self.argument_list();
+            self.emit_byte(OpCode::OpInvoke(name_index, arg_count));
+            self.last_type = RoxType::Any;
+        } else {
+            self.emit_byte(OpCode::OpGetProperty(name_index));
+            self.last_type = RoxType::Any;
+        }
+    }
+
+    /// Compiles `a?.b` and `a?[i]`. When the receiver is nil the rest of
+    /// the access chain is skipped and nil is left as the result.
+    fn optional_chain(&mut self, _can_assign: bool) {
+        let nil_jump = self.emit_jump(OpCode::OpJumpIfNil(None));
+
+        match self.previous().token_type {
+            TokenType::QuestionDot => self.dot(false),
+            _ => self.index(false),
+        }
+
+        // the remaining accesses in the chain are skipped along with this one
+        loop {
+            if self.match_token(TokenType::Dot) {
+                self.dot(false);
+            } else if self.match_token(TokenType::LeftBracket) {
+                self.index(false);
+            } else if self.match_token(TokenType::LeftParen) {
+                self.call(false);
+            } else if self.match_token(TokenType::QuestionDot)
+                || self.match_token(TokenType::QuestionLeftBracket)
+            {
+                self.optional_chain(false);
+            } else {
+                break;
+            }
+        }
+
+        self.patch_jump(nil_jump, OpCode::OpJumpIfNil(None));
+        self.last_type = RoxType::Any;
+    }
+
+    fn match_expression(&mut self, _can_assign: bool) {
+        let match_token = self.previous().clone();
+
+        self.consume(TokenType::LeftParen, "Expect '(' after 'match'.");
+        self.expression();
+        self.consume(TokenType::RightParen, "Expect ')' after match value.");
+        self.consume(TokenType::LeftBrace, "Expect '{' before match arms.");
+
+        let mut end_jumps = vec![];
+        let mut has_default = false;
+        let mut matched_enum: Option<EnumDeclaration> = None;
+        let mut covered_variants: Vec<Rc<RoxString>> = vec![];
+
+        while !self.check_token(TokenType::RightBrace) && !self.check_token(TokenType::EOF) {
+            if has_default {
+                self.warning_at(self.peek(), "Unreachable match arm after 'default'.");
+            }
+
+            if self.match_token(TokenType::Default) {
+                has_default = true;
+                self.consume(TokenType::FatArrow, "Expect '=>' after match pattern.");
+
+                // the default arm always matches, so drop the match value
+                self.emit_byte(OpCode::OpPop);
+                self.expression();
+                end_jumps.push(self.emit_jump(OpCode::OpJump(None)));
+            } else {
+                // pattern tests leave a boolean above the match value
+                let bindings = match self.match_pattern() {
+                    Some(MatchPattern::Variant(declaration, variant, bindings)) => {
+                        if matched_enum.is_none() {
+                            matched_enum = Some(declaration.clone());
+                        }
+                        if matched_enum.as_ref().map(|e| &e.name) == Some(&declaration.name) {
+                            covered_variants.push(variant);
+                        }
+                        bindings
+                    }
+                    Some(MatchPattern::Value) => vec![],
+                    None => return,
+                };
+                self.consume(TokenType::FatArrow, "Expect '=>' after match pattern.");
+
+                let next_arm = self.emit_jump(OpCode::OpJumpIfFalse(None));
+                self.emit_byte(OpCode::OpPop);
+
+                if !bindings.is_empty() {
+                    self.emit_byte(OpCode::OpBindPayload(bindings.len()));
+                }
+                self.emit_byte(OpCode::OpPop);
+
+                let num_bindings = bindings.len();
+                self.match_bindings.extend(bindings);
+                self.expression();
+                self.match_bindings
+                    .truncate(self.match_bindings.len() - num_bindings);
+
+                if num_bindings > 0 {
+                    self.emit_byte(OpCode::OpUnbind(num_bindings));
+                }
+                end_jumps.push(self.emit_jump(OpCode::OpJump(None)));
+
+                self.patch_jump(next_arm, OpCode::OpJumpIfFalse(None));
+                self.emit_byte(OpCode::OpPop);
+            }
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after match arms.");
+
+        if !has_default {
+            self.emit_byte(OpCode::OpMatchError);
+
+            if let Some(declaration) = matched_enum {
+                let missing = declaration
+                    .variants
+                    .iter()
+                    .filter(|variant| !covered_variants.contains(&variant.name))
+                    .map(|variant| variant.name.to_string())
+                    .collect::<Vec<_>>();
+
+                if !missing.is_empty() {
+                    self.warning_at(
+                        &match_token,
+                        &format!(
+                            "Non-exhaustive match on enum '{}': missing {}.",
+                            declaration.name,
+                            missing.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+
+        for jump in end_jumps {
+            self.patch_jump(jump, OpCode::OpJump(None));
+        }
+
+        self.last_type = RoxType::Any;
+    }
+
+    /// Compiles the test for a single match arm pattern, leaving a boolean
+    /// on top of the match value. Enum variant patterns also return the
+    /// names their payload fields are bound to in the arm body.
+    fn match_pattern(&mut self) -> Option<MatchPattern> {
+        let token = self.advance().clone();
+
+        match &token.token_type {
+            TokenType::Identifier(id) => {
+                let declaration = match self.enums.resolve(id) {
+                    Some(declaration) => declaration.clone(),
+                    None => {
+                        self.error(&format!("Unknown enum '{}' in match pattern.", id));
+                        return None;
+                    }
+                };
+
+                let variant = self.enum_variant_name(&declaration)?;
+                let tag = self.emit_variant_tag(&declaration, &variant);
+
+                let mut bindings = vec![];
+                if self.match_token(TokenType::LeftParen) {
+                    if !self.check_token(TokenType::RightParen) {
+                        loop {
+                            bindings.push(self.consume_identifier("Expect payload binding name.")?);
+                            if !self.match_token(TokenType::Comma) {
+                                break;
+                            }
+                        }
+                    }
+                    self.consume(TokenType::RightParen, "Expect ')' after payload bindings.");
+
+                    let arity = declaration.variant(&variant).map_or(0, |v| v.arity);
+                    if bindings.len() != arity {
+                        self.error(&format!(
+                            "Variant '{}.{}' has {} payload values but {} were bound.",
+                            declaration.name,
+                            variant,
+                            arity,
+                            bindings.len()
+                        ));
+                    }
+                }
+
+                self.emit_byte(OpCode::OpMatchVariant(tag));
+                Some(MatchPattern::Variant(declaration, variant, bindings))
+            }
+            TokenType::Number(_) | TokenType::Minus => {
+                let low = self.match_number(&token)?;
+
+                if self.match_token(TokenType::DotDot) {
+                    let high_token = self.advance().clone();
+                    let high = self.match_number(&high_token)?;
+                    let low = self.chunk.make_constant(Value::Number(low));
+                    let high = self.chunk.make_constant(Value::Number(high));
+                    self.emit_byte(OpCode::OpMatchRange(low, high));
+                } else {
+                    self.emit_byte(OpCode::OpDup);
+                    self.emit_constant(Value::Number(low), token.line);
+                    self.emit_byte(OpCode::OpEqual);
+                }
+                Some(MatchPattern::Value)
+            }
+            TokenType::StringLiteral(string) => {
+                self.emit_byte(OpCode::OpDup);
+                self.string(string, token.line, false);
+                self.emit_byte(OpCode::OpEqual);
+                Some(MatchPattern::Value)
+            }
+            TokenType::True | TokenType::False | TokenType::Nil => {
+                self.emit_byte(OpCode::OpDup);
+                self.literal(false);
+                self.emit_byte(OpCode::OpEqual);
+                Some(MatchPattern::Value)
+            }
+            _ => {
+                self.error("Expect match pattern.");
+                None
+            }
+        }
+    }
+
+    /// Reads an optionally negated number literal in a match pattern.
+    fn match_number(&mut self, token: &Token) -> Option<RoxNumber> {
+        let (token, negate) = match token.token_type {
+            TokenType::Minus => (self.advance().clone(), true),
+            _ => (token.clone(), false),
+        };
+
+        match token.token_type {
+            TokenType::Number(num) if negate => Some(-num),
+            TokenType::Number(num) => Some(num),
+            _ => {
+                self.error("Expect number in match pattern.");
+                None
+            }
+        }
+    }
+
+    /// Compiles `Enum.Variant` or `Enum.Variant(payload, ...)` into a
+    /// new variant value.
+    fn enum_variant(&mut self, declaration: &EnumDeclaration, line: usize) {
+        let variant = match self.enum_variant_name(declaration) {
+            Some(variant) => variant,
+            None => return,
+        };
+        let arity = declaration.variant(&variant).map_or(0, |v| v.arity);
+        let tag = self.emit_variant_tag(declaration, &variant);
+
+        self.last_type = RoxType::Enum;
+        if arity == 0 {
+            self.emit_byte(OpCode::OpConstant(tag));
+            return;
+        }
+
+        self.consume(TokenType::LeftParen, "Expect '(' before variant payload.");
+        let mut arg_count = 0;
+        if !self.check_token(TokenType::RightParen) {
+            loop {
+                self.expression();
+                arg_count += 1;
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after variant payload.");
+
+        if arg_count != arity {
+            self.error(&format!(
+                "Variant '{}.{}' expects {} payload values but got {}.",
+                declaration.name, variant, arity, arg_count
+            ));
+        }
+
+        self.chunk.write_chunk(OpCode::OpVariant(tag, arity), line);
+        self.last_type = RoxType::Enum;
+    }
+
+    fn enum_variant_name(&mut self, declaration: &EnumDeclaration) -> Option<Rc<RoxString>> {
+        self.consume(TokenType::Dot, "Expect '.' after enum name.");
+        let variant = self.consume_identifier("Expect variant name after '.'.")?;
+
+        if !declaration.has_variant(&variant) {
+            self.error(&format!(
+                "Enum '{}' has no variant '{}'.",
+                declaration.name, variant
+            ));
+            return None;
+        }
+
+        Some(variant)
+    }
+
+    /// Writes the payload-less tag of a variant to the constants
+    /// array and returns its index.
+    fn emit_variant_tag(&mut self, declaration: &EnumDeclaration, variant: &RoxString) -> usize {
+        let tag = RoxEnumVariant::new(&declaration.name, variant);
+        self.chunk
+            .make_constant(Value::Object(RoxObject::new(ObjectType::ObjEnumVariant(
+                tag,
+            ))))
     }
 
     fn string(&mut self, string: &Rc<RoxString>, line: usize, _can_assign: bool) {
         let new_rox_object =
             RoxObject::new(ObjectType::ObjString(RoxString::new(&Rc::clone(string))));
         self.emit_constant(Value::Object(new_rox_object), line);
+        self.last_type = RoxType::String;
     }
 
     fn variable(&mut self, id: &Rc<RoxString>, line: usize, can_assign: bool) {
+        // payload bindings of the enclosing match arms shadow everything else
+        if let Some(binding_idx) = self.match_bindings.iter().rposition(|name| name == id) {
+            if can_assign && self.match_token(TokenType::Equal) {
+                self.error("Can't assign to a match binding.");
+                return;
+            }
+            self.emit_byte(OpCode::OpGetBinding(binding_idx));
+            return;
+        }
+
+        if self.check_token(TokenType::Dot) {
+            if let Some(declaration) = self.enums.resolve(id).cloned() {
+                self.enum_variant(&declaration, line);
+                return;
+            }
+        }
+
         let (is_initialized, is_local_id) = self.locals.resolve_local(id);
 
         if !is_initialized {
             self.error("Can't read local variable in its own initializer.");
         }
 
+        let name = self.previous().clone();
+
         // locals live on the stack at runtime
         if let Some(local_idx) = is_local_id {
+            let annotation = self.locals.annotation(local_idx);
             if can_assign && self.match_token(TokenType::Equal) {
-                self.expression();
+                let inferred = self.typed_expression();
+                self.check_annotation(&name, annotation, inferred);
+                self.last_type = inferred;
                 self.emit_byte(OpCode::OpSetLocal(local_idx));
             } else {
                 self.emit_byte(OpCode::OpGetLocal(local_idx));
+                self.last_type = annotation;
             }
         } else {
             // globals live in globals list
+            let annotation = self.global_types.resolve(id);
             if can_assign && self.match_token(TokenType::Equal) {
-                self.expression();
+                let inferred = self.typed_expression();
+                self.check_annotation(&name, annotation, inferred);
+                self.last_type = inferred;
                 self.chunk
                     .add_identifier_constant(id, line, VariableOp::SetGlobal);
             } else {
                 self.chunk
                     .add_identifier_constant(id, line, VariableOp::GetGlobal);
+                self.last_type = annotation;
             }
         }
     }
@@ -622,6 +1873,11 @@ impl Compiler {
             TokenType::Nil => self.emit_byte(OpCode::OpNil),
             _ => (), // never will be here because literal only used for these three types
         }
+
+        self.last_type = match self.previous().token_type {
+            TokenType::Nil => RoxType::Nil,
+            _ => RoxType::Bool,
+        };
     }
 
     fn unary(&mut self, _can_assign: bool) {
@@ -633,8 +1889,16 @@ impl Compiler {
 
         // emit operator opcode
         match operator_type.token_type {
-            TokenType::Minus => self.emit_byte(OpCode::OpNegate),
-            TokenType::Bang => self.emit_byte(OpCode::OpNot),
+            TokenType::Minus => {
+                self.emit_byte(OpCode::OpNegate);
+                if self.last_type != RoxType::BigInt {
+                    self.last_type = RoxType::Number;
+                }
+            }
+            TokenType::Bang => {
+                self.emit_byte(OpCode::OpNot);
+                self.last_type = RoxType::Bool;
+            }
             _ => panic!(
                 "Error parsing unary expression. Unexpected token type: {}",
                 operator_type
@@ -649,7 +1913,24 @@ impl Compiler {
         let rule = Compiler::get_rule(&operator_type);
 
         // parse rule with next highest precedence (term -> factor, factor -> unary)
+        let lhs_type = self.last_type;
         self.parse(rule.precedence.get_next());
+        let rhs_type = self.last_type;
+
+        self.last_type = match operator_type.token_type {
+            TokenType::Plus => match (lhs_type, rhs_type) {
+                (RoxType::Number, RoxType::Number) => RoxType::Number,
+                (RoxType::String, RoxType::String) => RoxType::String,
+                (RoxType::BigInt, _) | (_, RoxType::BigInt) => RoxType::BigInt,
+                _ => RoxType::Any,
+            },
+            // arithmetic with a big integer operand gives a big integer
+            TokenType::Minus | TokenType::Star | TokenType::Slash => match (lhs_type, rhs_type) {
+                (RoxType::BigInt, _) | (_, RoxType::BigInt) => RoxType::BigInt,
+                _ => RoxType::Number,
+            },
+            _ => RoxType::Bool,
+        };
 
         // emit opcode for token type
         match operator_type.token_type {
@@ -705,12 +1986,22 @@ impl Compiler {
 
         let can_assign = precedence <= &Precedence::PrecAssign;
 
+        // each operand is inferred by its own prefix function,
+        // and is unknown unless that function sets a type
+        self.last_type = RoxType::Any;
+        self.element_types = None;
+
         // call prefix parsing function if present
         if let Some(parse_fn) = prefix_fn {
             self.apply_parse_fn(parse_fn, can_assign)
                 .expect("Error applying parse func");
         } else if self.previous().token_type == TokenType::EOF {
             return;
+        } else if let TokenType::Error(message) = &self.previous().token_type {
+            // the scanner reports its errors as tokens
+            let message = message.clone();
+            self.error(&message);
+            return;
         } else {
             self.error(&format!(
                 "No prefix function parsed for precedence {}.",
@@ -729,6 +2020,8 @@ impl Compiler {
             if let Some(parse_fn) = infix_fn {
                 self.apply_parse_fn(parse_fn, can_assign)
                     .expect("Error applying parse func");
+                // an operator result is no longer the literal
+                self.element_types = None;
             } else if self.previous().token_type == TokenType::EOF {
                 return;
             } else {
@@ -742,6 +2035,16 @@ impl Compiler {
         }
     }
 
+    /// Consumes an identifier token and returns its name.
+    fn consume_identifier(&mut self, msg: &str) -> Option<Rc<RoxString>> {
+        self.consume(TokenType::Identifier(Rc::new(RoxString::new(""))), msg);
+
+        match &self.previous().token_type {
+            TokenType::Identifier(name) => Some(Rc::clone(name)),
+            _ => None,
+        }
+    }
+
     fn parse_variable(&mut self, msg: &str) -> usize {
         // TODO -- how to make parse variable work here without consuming blank ID?
         self.consume(TokenType::Identifier(Rc::new(RoxString::new(""))), msg);
@@ -764,18 +2067,177 @@ impl Compiler {
         self.emit_identifier_constant(previous_token_value, previous.line, VariableOp::Define)
     }
 
-    pub fn compile(source: &str) -> Result<Chunk, String> {
+    fn compile_declarations(&mut self) {
+        // parse sequence of declarations and statements
+        while !self.match_token(TokenType::EOF) {
+            self.declaration();
+        }
+
+        // emit final byte code
+        self.end_compiler();
+    }
+
+    /// Compiles source that may use the enums and global annotations
+    /// declared by earlier compilations, and adds the ones it declares
+    /// to them. The error contains the compiler's messages, one per
+    /// line.
+    pub fn compile(
+        source: &str,
+        enums: &mut Enums,
+        global_types: &mut GlobalTypes,
+    ) -> Result<Compiled, String> {
+        Self::compile_source(source, enums, global_types, false, false)
+            .map_err(|errors| errors.join("\n"))
+    }
+
+    /// Compiles source with runtime type guards inserted wherever a
+    /// value is stored into a variable with a type annotation.
+    pub fn compile_with_type_guards(
+        source: &str,
+        enums: &mut Enums,
+        global_types: &mut GlobalTypes,
+    ) -> Result<Compiled, String> {
+        Self::compile_source(source, enums, global_types, true, false)
+            .map_err(|errors| errors.join("\n"))
+    }
+
+    /// Compiles source for `eval`. A final expression statement, whose
+    /// semicolon may be left out, leaves its value on the stack. The
+    /// error contains the compiler's messages.
+    pub fn compile_eval(
+        source: &str,
+        enums: &mut Enums,
+        global_types: &mut GlobalTypes,
+        type_guards: bool,
+    ) -> Result<Compiled, String> {
+        Self::compile_source(source, enums, global_types, type_guards, true)
+            .map_err(|errors| errors.join(" "))
+    }
+
+    fn compile_source(
+        source: &str,
+        enums: &mut Enums,
+        global_types: &mut GlobalTypes,
+        type_guards: bool,
+        trailing_value: bool,
+    ) -> Result<Compiled, Vec<String>> {
         let tokens = Scanner::new().scan_tokens(source);
         let mut compiler = Self::new(tokens.to_vec());
+        compiler.enums = enums.clone();
+        compiler.global_types = global_types.clone();
+        compiler.type_guards = type_guards;
+        compiler.trailing_value = trailing_value;
 
-        // parse sequence of declarations and statements
-        while !compiler.match_token(TokenType::EOF) {
-            compiler.declaration();
+        compiler.compile_declarations();
+
+        if *compiler.had_error.borrow() {
+            return Err(compiler.errors.take());
         }
 
-        // emit final byte code
-        compiler.end_compiler();
+        *enums = compiler.enums;
+        *global_types = compiler.global_types;
+        Ok(Compiled {
+            chunk: compiler.chunk,
+            warnings: compiler.warnings.take(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile_tokens(source: &str) -> Compiler {
+        let tokens = Scanner::new().scan_tokens(source);
+        let mut compiler = Compiler::new(tokens.to_vec());
+        compiler.compile_declarations();
+        compiler
+    }
+
+    #[test]
+    fn test_non_exhaustive_enum_match_warns() {
+        let compiler = compile_tokens(
+            "enum Color { Red, Green, Blue(r) } print match (Color.Red) { Color.Red => 1 };",
+        );
+
+        assert!(!*compiler.had_error.borrow());
+        assert_eq!(
+            *compiler.warnings.borrow(),
+            vec![
+                "Warning at [1, 42] with message: Non-exhaustive match on enum 'Color': missing Green, Blue."
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exhaustive_enum_match_does_not_warn() {
+        let compiler = compile_tokens(
+            "enum Color { Red, Blue(r) } print match (Color.Red) { Color.Red => 1, Color.Blue(r) => r };",
+        );
 
-        Ok(compiler.chunk.clone())
+        assert!(compiler.warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_type_inference_covers_only_the_stored_expression() {
+        let accepted = [
+            "var n: Number = 1; var l = [\"a\"]; var s: String = l[0];",
+            "var s: String = \"a\"; var n: Number = s.len();",
+            "var b: Bool = 1 < 2; var m: Map = {a: b};",
+            "var n: Number = 1; var s: String = \"a\"; s = s + s;",
+        ];
+        for source in accepted {
+            let compiler = compile_tokens(source);
+            assert!(!*compiler.had_error.borrow(), "{}", source);
+        }
+
+        let rejected = [
+            "var s: String = 1;",
+            "var n: Number = 1; n = \"a\";",
+            "{ var n: Number = 1; n = [n]; }",
+            "var s: String = \"a\"; var n: Number = (s = s);",
+        ];
+        for source in rejected {
+            let compiler = compile_tokens(source);
+            assert!(*compiler.had_error.borrow(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_destructuring_checks_annotations() {
+        let accepted = [
+            "var x: Number = 1; var y; [x, y] = [2, \"str\"];",
+            "var x: Number = 1; var y; [x, y] = [[\"str\"][0], 2];",
+            "var x: Number = 1; var y; [y, x] = [\"a\"] + [\"b\", \"c\"];",
+            "var x: Number = 1; var [x] = [\"str\"]; x = \"s\";",
+        ];
+        for source in accepted {
+            let compiler = compile_tokens(source);
+            assert!(!*compiler.had_error.borrow(), "{}", source);
+        }
+
+        let rejected = [
+            "var x: Number = 1; var y; [x, y] = [\"str\", 2];",
+            "var x: Number = 1; var y; [x, y] = (\"str\", 2);",
+            "{ var x: Number = 1; var y; [y, x] = [1, \"str\"]; }",
+            "var rest: String = \"a\"; var y; [y, ...rest] = [1, 2];",
+        ];
+        for source in rejected {
+            let compiler = compile_tokens(source);
+            assert!(*compiler.had_error.borrow(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_function_declarations_are_rejected() {
+        let compiler = compile_tokens(
+            "fun f(a: String) -> Bool { if (true) { return a; } } var after = 1; after = \"a\";",
+        );
+
+        assert_eq!(
+            *compiler.errors.borrow(),
+            vec!["Error at [1, 1] with message: Functions are not supported yet, so parameters and return values cannot be annotated.".to_string()]
+        );
     }
 }
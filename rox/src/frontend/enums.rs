@@ -0,0 +1,72 @@
+use crate::{RoxString, DEBUG_MODE};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumVariant {
+    pub name: Rc<RoxString>,
+    pub arity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumDeclaration {
+    pub name: Rc<RoxString>,
+    pub variants: Vec<EnumVariant>,
+}
+
+impl EnumDeclaration {
+    pub fn new(name: &Rc<RoxString>) -> EnumDeclaration {
+        EnumDeclaration {
+            name: Rc::clone(name),
+            variants: vec![],
+        }
+    }
+
+    pub fn variant(&self, name: &RoxString) -> Option<&EnumVariant> {
+        self.variants.iter().find(|variant| *variant.name == *name)
+    }
+
+    pub fn has_variant(&self, name: &RoxString) -> bool {
+        self.variant(name).is_some()
+    }
+
+    pub fn add_variant(&mut self, name: &Rc<RoxString>, arity: usize) {
+        self.variants.push(EnumVariant {
+            name: Rc::clone(name),
+            arity,
+        });
+    }
+}
+
+/// The enums declared so far. Enums are resolved statically by the
+/// compiler so that variant construction and match exhaustiveness
+/// can be checked at compile time, and the VM keeps them alongside
+/// its globals so that `eval` and later calls to `interpret` see them.
+#[derive(Debug, Default, Clone)]
+pub struct Enums {
+    declarations: Vec<EnumDeclaration>,
+}
+
+impl Enums {
+    pub fn new() -> Enums {
+        Enums {
+            declarations: vec![],
+        }
+    }
+
+    pub fn declare(&mut self, declaration: EnumDeclaration) {
+        if DEBUG_MODE {
+            println!("Declared enum {}", declaration.name);
+        }
+
+        // a redeclaration replaces the previous enum of the same name
+        self.declarations
+            .retain(|existing| existing.name != declaration.name);
+        self.declarations.push(declaration);
+    }
+
+    pub fn resolve(&self, name: &RoxString) -> Option<&EnumDeclaration> {
+        self.declarations
+            .iter()
+            .find(|declaration| *declaration.name == *name)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{RoxString, Token, TokenType, DEBUG_MODE};
+use crate::{RoxString, RoxType, Token, TokenType, DEBUG_MODE};
 
 use super::LOCALS_COUNT;
 
@@ -6,6 +6,7 @@ use super::LOCALS_COUNT;
 pub struct Local {
     pub name: Option<Token>,
     pub depth: Option<usize>,
+    pub annotation: RoxType,
 }
 
 impl Local {
@@ -13,6 +14,7 @@ impl Local {
         Local {
             name: Some(name.clone()),
             depth: Some(depth),
+            annotation: RoxType::Any,
         }
     }
 }
@@ -36,6 +38,15 @@ impl Locals {
         self.locals[self.count - 1].depth = Some(scope_depth);
     }
 
+    /// Records the type annotation of the most recently added local.
+    pub fn annotate_variable(&mut self, annotation: RoxType) {
+        self.locals[self.count - 1].annotation = annotation;
+    }
+
+    pub fn annotation(&self, index: usize) -> RoxType {
+        self.locals[index].annotation
+    }
+
     pub fn add_local(&mut self, token: &Token, depth: usize) {
         self.locals[self.count] = Local::new(token, depth);
         self.count += 1;
@@ -1,5 +1,7 @@
+mod enums;
 mod locals;
 
+pub use enums::{EnumDeclaration, Enums};
 pub use locals::Local;
 pub use locals::Locals;
 
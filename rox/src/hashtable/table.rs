@@ -52,12 +52,18 @@ where
     }
 }
 
-#[derive(Debug, Default)]
-pub struct StdTable<K, V> {
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StdTable<K, V>
+where
+    K: Hash + Eq,
+{
     inner_table: HashMap<K, Entry<K, V>>,
 }
 
-impl<K, V> StdTable<K, V> {
+impl<K, V> StdTable<K, V>
+where
+    K: Hash + Eq,
+{
     pub fn new() -> StdTable<K, V> {
         let inner_table: HashMap<K, Entry<K, V>> = HashMap::new();
         StdTable { inner_table }
@@ -66,4 +72,20 @@ impl<K, V> StdTable<K, V> {
     pub fn reset(&mut self) {
         self.inner_table.drain();
     }
+
+    pub fn len(&self) -> usize {
+        self.inner_table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner_table.is_empty()
+    }
+
+    /// Iterates over the key-value pairs in the table
+    /// in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.inner_table
+            .values()
+            .map(|entry| (&entry.key, &entry.value))
+    }
 }
@@ -3,6 +3,7 @@ mod compiler;
 mod error;
 mod frontend;
 mod hashtable;
+mod natives;
 mod object;
 mod opcode;
 mod precedence;
@@ -20,6 +21,7 @@ pub use compiler::*;
 pub use error::*;
 pub use hashtable::RoxMap;
 pub use hashtable::Table;
+pub use natives::NativeContext;
 pub use object::*;
 pub use opcode::OpCode;
 pub use precedence::Precedence;
@@ -28,6 +30,7 @@ pub use run::*;
 pub use scanner::Scanner;
 pub use stack::Stack;
 pub use token::*;
+pub use types::RoxType;
 pub use value::*;
 pub use vm::*;
 
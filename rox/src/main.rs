@@ -12,9 +12,23 @@ fn main() {
 
     if config.is_repl {
         config.repl();
+    } else if config.is_test {
+        match config.run_tests() {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(msg) => {
+                eprintln!("\n<<<Error in Rox interpreter>>>\n\nMessage: {}", msg);
+                std::process::exit(1);
+            }
+        }
     } else {
         config.run_file().unwrap_or_else(|msg| {
             eprintln!("\n<<<Error in Rox interpreter>>>\n\nMessage: {}", msg);
+            std::process::exit(1);
         });
+
+        if let Some(code) = config.exit_code() {
+            std::process::exit(code);
+        }
     }
 }
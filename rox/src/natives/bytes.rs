@@ -0,0 +1,43 @@
+use super::{list_arg, string_arg};
+use crate::{NativeContext, ObjectType, RoxBytes, RoxNative, RoxObject, Value};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("bytes", 1, bytes),
+        RoxNative::new("from_hex", 1, from_hex),
+        RoxNative::new("from_base64", 1, from_base64),
+    ]
+}
+
+fn bytes_value(bytes: RoxBytes) -> Value {
+    Value::Object(RoxObject::new(ObjectType::ObjBytes(bytes)))
+}
+
+/// Creates bytes from a list of whole numbers from 0 to 255.
+fn bytes(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let values = list_arg("bytes", &args[0])?.values().clone();
+    let bytes = values
+        .iter()
+        .map(|value| match value {
+            Value::Number(num) if num.fract() == 0.0 && (0.0..=255.0).contains(&**num) => {
+                Ok(**num as u8)
+            }
+            _ => Err(format!(
+                "bytes expects numbers from 0 to 255 but got {}.",
+                value
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(bytes_value(RoxBytes::new(bytes)))
+}
+
+fn from_hex(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let text = string_arg("from_hex", &args[0])?;
+    RoxBytes::from_hex(&text).map(bytes_value)
+}
+
+fn from_base64(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let text = string_arg("from_base64", &args[0])?;
+    RoxBytes::from_base64(&text).map(bytes_value)
+}
@@ -0,0 +1,13 @@
+use super::string_arg;
+use crate::{NativeContext, RoxNative, Value};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![RoxNative::new("eval", 1, eval)]
+}
+
+/// Hands source to the VM, which compiles and runs it against the
+/// current globals once the native returns.
+fn eval(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    context.pending_eval = Some(string_arg("eval", &args[0])?);
+    Ok(Value::Nil)
+}
@@ -0,0 +1,149 @@
+use super::{string_arg, string_value};
+use crate::{NativeContext, ObjectType, RoxList, RoxNative, RoxObject, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("read_file", 1, read_file),
+        RoxNative::new("write_file", 2, write_file),
+        RoxNative::new("append_file", 2, append_file),
+        RoxNative::new("read_lines", 1, read_lines),
+        RoxNative::new("exists", 1, exists),
+        RoxNative::new("list_dir", 1, list_dir),
+        RoxNative::new("remove_file", 1, remove_file),
+    ]
+}
+
+/// Resolves a script path against the sandbox root, if one is set.
+/// Paths that leave the root, whether through `..`, an absolute path
+/// or a symlink, are rejected.
+pub fn resolve_path(root: Option<&Path>, path: &str) -> Result<PathBuf, String> {
+    let root = match root {
+        Some(root) => root,
+        None => return Ok(PathBuf::from(path)),
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in root.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            component => resolved.push(component),
+        }
+    }
+
+    // symlinks are only visible on the part of the path that exists,
+    // where a link exists even when its target does not
+    let mut existing = resolved.as_path();
+    while existing.symlink_metadata().is_err() {
+        existing = match existing.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+
+    // a dangling link cannot be canonicalized, and writing through
+    // it would create its target wherever it points
+    let inside_root = match existing.canonicalize() {
+        Ok(canonical) => canonical.starts_with(root),
+        Err(_) => false,
+    };
+
+    if resolved.starts_with(root) && inside_root {
+        Ok(resolved)
+    } else {
+        Err(format!("Path '{}' is outside of the sandbox.", path))
+    }
+}
+
+fn path_arg(context: &NativeContext, name: &str, value: &Value) -> Result<PathBuf, String> {
+    let path = string_arg(name, value)?;
+    resolve_path(context.sandbox_root.as_deref(), &path)
+}
+
+fn io_error(name: &str, path: &Path, error: std::io::Error) -> String {
+    format!("{} failed for '{}': {}.", name, path.display(), error)
+}
+
+fn read_file(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "read_file", &args[0])?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(string_value(contents)),
+        Err(error) => Err(io_error("read_file", &path, error)),
+    }
+}
+
+fn write_file(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "write_file", &args[0])?;
+    let contents = string_arg("write_file", &args[1])?;
+    match fs::write(&path, contents.as_bytes()) {
+        Ok(()) => Ok(Value::Nil),
+        Err(error) => Err(io_error("write_file", &path, error)),
+    }
+}
+
+fn append_file(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "append_file", &args[0])?;
+    let contents = string_arg("append_file", &args[1])?;
+    let appended = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()));
+
+    match appended {
+        Ok(()) => Ok(Value::Nil),
+        Err(error) => Err(io_error("append_file", &path, error)),
+    }
+}
+
+fn read_lines(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "read_lines", &args[0])?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            let lines = contents
+                .lines()
+                .map(|line| string_value(line.to_string()))
+                .collect();
+            Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+                RoxList::new(lines),
+            ))))
+        }
+        Err(error) => Err(io_error("read_lines", &path, error)),
+    }
+}
+
+fn exists(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "exists", &args[0])?;
+    Ok(Value::Boolean(path.exists()))
+}
+
+/// Lists the names of the entries in a directory in sorted order.
+fn list_dir(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "list_dir", &args[0])?;
+    let entries = fs::read_dir(&path).map_err(|error| io_error("list_dir", &path, error))?;
+
+    let mut names = vec![];
+    for entry in entries {
+        let entry = entry.map_err(|error| io_error("list_dir", &path, error))?;
+        names.push(entry.file_name().to_string_lossy().to_string());
+    }
+    names.sort();
+
+    let names = names.into_iter().map(string_value).collect();
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+        RoxList::new(names),
+    ))))
+}
+
+fn remove_file(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let path = path_arg(context, "remove_file", &args[0])?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(Value::Nil),
+        Err(error) => Err(io_error("remove_file", &path, error)),
+    }
+}
@@ -0,0 +1,85 @@
+use super::string_arg;
+use crate::{NativeContext, ObjectType, RoxNative, RoxObject, RoxString, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("input", 1, input),
+        RoxNative::new("read_line", 0, read_line),
+        RoxNative::new("read_all", 0, read_all),
+    ]
+}
+
+/// The source that the input natives read from.
+pub struct Input(Box<dyn BufRead>);
+
+impl Input {
+    pub fn new(reader: Box<dyn BufRead>) -> Input {
+        Input(reader)
+    }
+
+    pub fn stdin() -> Input {
+        Input::new(Box::new(BufReader::new(io::stdin())))
+    }
+
+    /// Reads the next line without its line ending, or None at EOF.
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if self.0.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+
+    /// Reads everything up to EOF, or None if nothing is left.
+    fn read_all(&mut self) -> io::Result<Option<String>> {
+        let mut contents = String::new();
+        if self.0.read_to_string(&mut contents)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(contents))
+    }
+}
+
+impl std::fmt::Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Input")
+    }
+}
+
+fn line_value(name: &str, line: io::Result<Option<String>>) -> Result<Value, String> {
+    match line {
+        Ok(Some(line)) => Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+            RoxString::new(&line),
+        )))),
+        Ok(None) => Ok(Value::Nil),
+        Err(error) => Err(format!("{} failed: {}.", name, error)),
+    }
+}
+
+/// Prints prompt and reads the line typed in response.
+fn input(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let prompt = string_arg("input", &args[0])?;
+
+    let mut stdout = io::stdout();
+    write!(stdout, "{}", prompt)
+        .and_then(|_| stdout.flush())
+        .map_err(|error| format!("input failed: {}.", error))?;
+
+    line_value("input", context.input.read_line())
+}
+
+fn read_line(context: &mut NativeContext, _args: &[Value]) -> Result<Value, String> {
+    line_value("read_line", context.input.read_line())
+}
+
+fn read_all(context: &mut NativeContext, _args: &[Value]) -> Result<Value, String> {
+    line_value("read_all", context.input.read_all())
+}
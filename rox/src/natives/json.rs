@@ -0,0 +1,430 @@
+use super::{number_arg, string_arg};
+use crate::{
+    NativeContext, ObjectType, RoxDict, RoxList, RoxNative, RoxNumber, RoxObject, RoxString, Value,
+};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// How deeply arrays and objects may nest, which keeps parsing and
+/// writing them from running out of stack.
+const MAX_DEPTH: usize = 512;
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("json_parse", 1, json_parse),
+        RoxNative::with_optional("json_stringify", 2, 1, json_stringify),
+    ]
+}
+
+fn json_parse(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let source = string_arg("json_parse", &args[0])?;
+    JsonParser::new(&source).parse()
+}
+
+/// Converts a value to JSON. An indent of 0, nil or no indent gives
+/// compact output, otherwise nested values are indented by that many
+/// spaces.
+fn json_stringify(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let indent = match &args[1] {
+        Value::Nil => 0,
+        value => {
+            let indent = number_arg("json_stringify", value)?;
+            if indent.fract() != 0.0 || indent < 0.0 {
+                return Err(format!(
+                    "json_stringify expects a non-negative whole indent but got {}.",
+                    value
+                ));
+            }
+            indent as usize
+        }
+    };
+
+    let mut writer = JsonWriter {
+        indent,
+        output: String::new(),
+        path: vec![],
+    };
+    writer.write_value(&args[0], 0)?;
+
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+        RoxString::new(&writer.output),
+    ))))
+}
+
+/// A recursive descent JSON parser which tracks the line and
+/// column of the input for error messages.
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    // the number of arrays and objects around the current value
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(source: &'a str) -> JsonParser<'a> {
+        JsonParser {
+            chars: source.chars().peekable(),
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn parse(&mut self) -> Result<Value, String> {
+        let value = self.value()?;
+        self.skip_whitespace();
+
+        match self.chars.peek() {
+            None => Ok(value),
+            Some(_) => Err(self.error("Unexpected trailing characters")),
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!(
+            "json_parse error at [{}, {}]: {}.",
+            self.line, self.column, message
+        )
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let next = self.chars.next();
+        match next {
+            Some('\n') => {
+                self.line += 1;
+                self.column = 1;
+            }
+            Some(_) => self.column += 1,
+            None => {}
+        }
+        next
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.chars.peek() {
+            Some(c) if *c == expected => {
+                self.advance();
+                Ok(())
+            }
+            _ => Err(self.error(&format!("Expect '{}'", expected))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.chars.peek() {
+            self.advance();
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+
+        match self.chars.peek() {
+            Some('{') => self.nested(Self::object),
+            Some('[') => self.nested(Self::array),
+            Some('"') => {
+                let string = self.string()?;
+                Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+                    RoxString::new(&string),
+                ))))
+            }
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.keyword("true", Value::Boolean(true)),
+            Some('f') => self.keyword("false", Value::Boolean(false)),
+            Some('n') => self.keyword("null", Value::Nil),
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+        for expected in keyword.chars() {
+            if self.chars.peek() != Some(&expected) {
+                return Err(self.error(&format!("Expect '{}'", keyword)));
+            }
+            self.advance();
+        }
+        Ok(value)
+    }
+
+    /// Parses an array or object one level deeper than the current value.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, String>) -> Result<Value, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!(
+                "Arrays and objects nest deeper than {} levels",
+                MAX_DEPTH
+            )));
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        self.expect('{')?;
+        let dict = RoxDict::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.advance();
+            return Ok(Value::Object(RoxObject::new(ObjectType::ObjDict(dict))));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek() != Some(&'"') {
+                return Err(self.error("Expect string as object key"));
+            }
+            let key = self.string()?;
+
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.value()?;
+            dict.set(&RoxString::new(&key), &value);
+
+            self.skip_whitespace();
+            match self.advance() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err(self.error("Expect ',' or '}' after object entry")),
+            }
+        }
+
+        Ok(Value::Object(RoxObject::new(ObjectType::ObjDict(dict))))
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect('[')?;
+        let mut values = vec![];
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.advance();
+            return Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+                RoxList::new(values),
+            ))));
+        }
+
+        loop {
+            values.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.advance() {
+                Some(',') => continue,
+                Some(']') => break,
+                _ => return Err(self.error("Expect ',' or ']' after array element")),
+            }
+        }
+
+        Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+            RoxList::new(values),
+        ))))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self.advance() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escaped = match self.advance() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    string.push(escaped);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("Unescaped control character in string"))
+                }
+                Some(c) => string.push(c),
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn hex_code(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .advance()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("Expect four hex digits after '\\u'"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    /// Reads the digits of a `\u` escape, combining surrogate pairs.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex_code()?;
+
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.advance() != Some('\\') || self.advance() != Some('u') {
+                return Err(self.error("Expect low surrogate after high surrogate"));
+            }
+            let low = self.hex_code()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("Invalid low surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape"))
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let mut number = String::new();
+        while let Some(c @ ('-' | '+' | '.' | 'e' | 'E' | '0'..='9')) = self.chars.peek() {
+            number.push(*c);
+            self.advance();
+        }
+
+        match number.parse::<f64>() {
+            Ok(num) if num.is_finite() => Ok(Value::Number(RoxNumber(num))),
+            _ => Err(self.error(&format!("Invalid number '{}'", number))),
+        }
+    }
+}
+
+struct JsonWriter {
+    indent: usize,
+    output: String,
+    // the lists and maps currently being written, to detect cycles
+    path: Vec<usize>,
+}
+
+impl JsonWriter {
+    fn write_value(&mut self, value: &Value, depth: usize) -> Result<(), String> {
+        match value {
+            Value::Nil => self.output.push_str("null"),
+            Value::Boolean(b) => self.output.push_str(&b.to_string()),
+            Value::Number(num) if num.is_finite() => self.output.push_str(&num.to_string()),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBigInt(int),
+            }) => self.output.push_str(&int.to_string()),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjString(string),
+            }) => self.write_string(string),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjList(list),
+            }) => {
+                self.enter(list.address())?;
+                let values = list.values().clone();
+                self.write_container('[', ']', &values, depth, |writer, value| {
+                    writer.write_value(value, depth + 1)
+                })?;
+                self.path.pop();
+            }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjTuple(tuple),
+            }) => self.write_container('[', ']', tuple.values(), depth, |writer, value| {
+                writer.write_value(value, depth + 1)
+            })?,
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjDict(dict),
+            }) => {
+                self.enter(dict.address())?;
+                let entries = dict
+                    .sorted_keys()
+                    .into_iter()
+                    .map(|key| {
+                        let value = dict.get(&key).unwrap_or_default();
+                        (key, value)
+                    })
+                    .collect::<Vec<_>>();
+                self.write_container('{', '}', &entries, depth, |writer, (key, value)| {
+                    writer.write_string(key);
+                    writer.output.push(':');
+                    if writer.indent > 0 {
+                        writer.output.push(' ');
+                    }
+                    writer.write_value(value, depth + 1)
+                })?;
+                self.path.pop();
+            }
+            _ => return Err(format!("Cannot convert {} to JSON.", value)),
+        }
+
+        Ok(())
+    }
+
+    fn enter(&mut self, address: usize) -> Result<(), String> {
+        if self.path.contains(&address) {
+            return Err(String::from(
+                "Cannot convert a list or map that contains itself to JSON.",
+            ));
+        }
+        self.path.push(address);
+        Ok(())
+    }
+
+    fn write_container<T>(
+        &mut self,
+        open: char,
+        close: char,
+        items: &[T],
+        depth: usize,
+        mut write_item: impl FnMut(&mut JsonWriter, &T) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if depth == MAX_DEPTH {
+            return Err(format!(
+                "Cannot convert values nested deeper than {} levels to JSON.",
+                MAX_DEPTH
+            ));
+        }
+
+        self.output.push(open);
+
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.newline(depth + 1);
+            write_item(self, item)?;
+        }
+
+        if !items.is_empty() {
+            self.newline(depth);
+        }
+        self.output.push(close);
+        Ok(())
+    }
+
+    fn newline(&mut self, depth: usize) {
+        if self.indent > 0 {
+            self.output.push('\n');
+            self.output.push_str(&" ".repeat(self.indent * depth));
+        }
+    }
+
+    fn write_string(&mut self, string: &str) {
+        self.output.push('"');
+        for c in string.chars() {
+            match c {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                c if (c as u32) < 0x20 => self.output.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+    }
+}
@@ -0,0 +1,132 @@
+use super::number_arg;
+use crate::{NativeContext, ObjectType, RoxBigInt, RoxNative, RoxNumber, RoxObject, Value};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("sqrt", 1, sqrt),
+        RoxNative::new("pow", 2, pow),
+        RoxNative::new("floor", 1, floor),
+        RoxNative::new("ceil", 1, ceil),
+        RoxNative::new("round", 1, round),
+        RoxNative::new("abs", 1, abs),
+        RoxNative::new("min", 2, min),
+        RoxNative::new("max", 2, max),
+        RoxNative::new("sin", 1, sin),
+        RoxNative::new("cos", 1, cos),
+        RoxNative::new("tan", 1, tan),
+        RoxNative::new("atan2", 2, atan2),
+        RoxNative::new("log", 1, log),
+        RoxNative::new("exp", 1, exp),
+        RoxNative::new("is_nan", 1, is_nan),
+        RoxNative::new("is_finite", 1, is_finite),
+        RoxNative::new("bigint", 1, bigint),
+    ]
+}
+
+pub fn constants() -> Vec<(&'static str, Value)> {
+    vec![
+        ("PI", number(std::f64::consts::PI)),
+        ("E", number(std::f64::consts::E)),
+        ("INF", number(f64::INFINITY)),
+        ("NAN", number(f64::NAN)),
+    ]
+}
+
+fn number(num: f64) -> Value {
+    Value::Number(RoxNumber(num))
+}
+
+fn sqrt(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("sqrt", &args[0])?.sqrt()))
+}
+
+fn pow(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let base = number_arg("pow", &args[0])?;
+    let exponent = number_arg("pow", &args[1])?;
+    Ok(number(base.powf(exponent)))
+}
+
+fn floor(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("floor", &args[0])?.floor()))
+}
+
+fn ceil(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("ceil", &args[0])?.ceil()))
+}
+
+fn round(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("round", &args[0])?.round()))
+}
+
+fn abs(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("abs", &args[0])?.abs()))
+}
+
+fn min(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let a = number_arg("min", &args[0])?;
+    let b = number_arg("min", &args[1])?;
+    Ok(number(a.min(b)))
+}
+
+fn max(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let a = number_arg("max", &args[0])?;
+    let b = number_arg("max", &args[1])?;
+    Ok(number(a.max(b)))
+}
+
+fn sin(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("sin", &args[0])?.sin()))
+}
+
+fn cos(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("cos", &args[0])?.cos()))
+}
+
+fn tan(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("tan", &args[0])?.tan()))
+}
+
+fn atan2(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let y = number_arg("atan2", &args[0])?;
+    let x = number_arg("atan2", &args[1])?;
+    Ok(number(y.atan2(x)))
+}
+
+/// The natural logarithm.
+fn log(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("log", &args[0])?.ln()))
+}
+
+fn exp(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(number(number_arg("exp", &args[0])?.exp()))
+}
+
+fn is_nan(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(number_arg("is_nan", &args[0])?.is_nan()))
+}
+
+fn is_finite(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(
+        number_arg("is_finite", &args[0])?.is_finite(),
+    ))
+}
+
+/// Converts a whole number or a string of decimal digits
+/// to a big integer.
+fn bigint(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let int = match &args[0] {
+        Value::Number(num) => RoxBigInt::from_f64(**num),
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjString(string),
+        }) => RoxBigInt::parse(string.trim()),
+        value => value.as_big_int().cloned(),
+    };
+
+    match int {
+        Some(int) => Ok(Value::Object(RoxObject::new(ObjectType::ObjBigInt(int)))),
+        None => Err(format!(
+            "bigint expects a whole number but got {}.",
+            args[0]
+        )),
+    }
+}
@@ -0,0 +1,157 @@
+mod bytes;
+mod eval;
+mod file;
+mod input;
+mod json;
+mod math;
+mod process;
+mod random;
+mod reflect;
+mod time;
+
+use crate::{ObjectType, RoxList, RoxNative, RoxObject, RoxSet, RoxString, Value};
+use input::Input;
+use random::Rng;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// The state shared by the native functions of a single VM.
+#[derive(Debug)]
+pub struct NativeContext {
+    rng: Rng,
+    sandbox_root: Option<PathBuf>,
+    input: Input,
+    exit_code: Option<i32>,
+    started: Instant,
+    pending_eval: Option<RoxString>,
+}
+
+impl NativeContext {
+    pub fn new() -> NativeContext {
+        NativeContext {
+            rng: Rng::from_clock(),
+            sandbox_root: None,
+            input: Input::stdin(),
+            exit_code: None,
+            started: Instant::now(),
+            pending_eval: None,
+        }
+    }
+
+    pub fn seed_random(&mut self, seed: u64) {
+        self.rng.seed(seed);
+    }
+
+    /// Restricts the file natives to paths inside root. Relative
+    /// paths used by scripts are resolved against root.
+    pub fn set_sandbox_root(&mut self, root: &Path) -> std::io::Result<()> {
+        self.sandbox_root = Some(root.canonicalize()?);
+        Ok(())
+    }
+
+    /// The status passed to `exit`, if the script called it.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn clear_exit_code(&mut self) {
+        self.exit_code = None;
+    }
+
+    /// The source passed to `eval` by the native just called, if any.
+    pub fn take_eval(&mut self) -> Option<RoxString> {
+        self.pending_eval.take()
+    }
+
+    /// Replaces stdin as the source read by the input natives.
+    pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+        self.input = Input::new(input);
+    }
+}
+
+impl Default for NativeContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The native functions defined as globals in every VM.
+pub fn natives() -> Vec<RoxNative> {
+    let mut natives = vec![
+        RoxNative::new("sort", 1, sort),
+        RoxNative::variadic("set", set),
+    ];
+    natives.extend(math::natives());
+    natives.extend(random::natives());
+    natives.extend(file::natives());
+    natives.extend(input::natives());
+    natives.extend(process::natives());
+    natives.extend(json::natives());
+    natives.extend(time::natives());
+    natives.extend(reflect::natives());
+    natives.extend(eval::natives());
+    natives.extend(bytes::natives());
+    natives
+}
+
+/// The constant values defined as globals in every VM.
+pub fn constants() -> Vec<(&'static str, Value)> {
+    math::constants()
+}
+
+fn number_arg(name: &str, value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(num) => Ok(**num),
+        _ => Err(format!("{} expects a number but got {}.", name, value)),
+    }
+}
+
+pub(crate) fn string_arg(name: &str, value: &Value) -> Result<RoxString, String> {
+    match value {
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjString(string),
+        }) => Ok(string.clone()),
+        _ => Err(format!("{} expects a string but got {}.", name, value)),
+    }
+}
+
+pub(crate) fn string_value(string: String) -> Value {
+    Value::Object(RoxObject::new(ObjectType::ObjString(RoxString::from(
+        string,
+    ))))
+}
+
+fn list_arg(name: &str, value: &Value) -> Result<RoxList, String> {
+    match value {
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjList(list),
+        }) => Ok(list.clone()),
+        _ => Err(format!("{} expects a list but got {}.", name, value)),
+    }
+}
+
+/// Returns a sorted copy of a list. Values of different types are
+/// ordered by `Value::total_cmp`, so any list can be sorted.
+fn sort(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let mut values = list_arg("sort", &args[0])?.values().clone();
+    values.sort_by(Value::total_cmp);
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+        RoxList::new(values),
+    ))))
+}
+
+/// Creates a set of the distinct values passed, as in `set(1, 2, 3)`.
+/// A single list is taken as the values instead, as in `set([1, 2, 3])`,
+/// which is also how sets print.
+fn set(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let values = match args {
+        [Value::Object(RoxObject {
+            object_type: ObjectType::ObjList(list),
+        })] => list.values().clone(),
+        _ => args.to_vec(),
+    };
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjSet(
+        RoxSet::new(&values)?,
+    ))))
+}
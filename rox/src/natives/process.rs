@@ -0,0 +1,31 @@
+use super::{number_arg, string_arg};
+use crate::{NativeContext, ObjectType, RoxNative, RoxObject, RoxString, Value};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("env", 1, env),
+        RoxNative::new("exit", 1, exit),
+    ]
+}
+
+/// Reads an environment variable, or nil if it is not set.
+fn env(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let name = string_arg("env", &args[0])?;
+    match std::env::var(&*name) {
+        Ok(value) => Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+            RoxString::new(&value),
+        )))),
+        Err(_) => Ok(Value::Nil),
+    }
+}
+
+/// Stops the script, asking the host to exit with code.
+fn exit(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let code = number_arg("exit", &args[0])?;
+    if code.fract() != 0.0 || code < i32::MIN as f64 || code > i32::MAX as f64 {
+        return Err(format!("exit expects a whole number but got {}.", args[0]));
+    }
+
+    context.exit_code = Some(code as i32);
+    Ok(Value::Nil)
+}
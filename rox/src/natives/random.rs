@@ -0,0 +1,138 @@
+use super::{list_arg, number_arg};
+use crate::{NativeContext, RoxNative, RoxNumber, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The most whole numbers random_int can choose between, which is
+/// where numbers stop being able to represent every integer.
+const MAX_SPAN: f64 = 9007199254740992.0;
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("random", 0, random),
+        RoxNative::new("random_int", 2, random_int),
+        RoxNative::new("shuffle", 1, shuffle),
+        RoxNative::new("choice", 1, choice),
+        RoxNative::new("seed", 1, seed),
+    ]
+}
+
+/// A small xorshift64* generator. It is not suitable for cryptography,
+/// but the same seed always replays the same sequence of numbers.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        let mut rng = Rng { state: 0 };
+        rng.seed(seed);
+        rng
+    }
+
+    /// Seeds the generator from the system clock.
+    pub fn from_clock() -> Rng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Rng::new(nanos)
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        // scramble the seed with splitmix64, since xorshift
+        // cannot start from a zero state
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        self.state = (z ^ (z >> 31)).max(1);
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a number in the range [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        // the top 53 bits fill an f64 mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns an index in the range [0, bound).
+    pub fn next_index(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+fn random(context: &mut NativeContext, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(RoxNumber(context.rng.next_f64())))
+}
+
+/// Returns a whole number between low and high, including both.
+fn random_int(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let low = number_arg("random_int", &args[0])?;
+    let high = number_arg("random_int", &args[1])?;
+    if low.fract() != 0.0 || high.fract() != 0.0 || low > high {
+        return Err(format!(
+            "random_int expects two whole numbers with low <= high but got {} and {}.",
+            args[0], args[1]
+        ));
+    }
+
+    // every whole number in the span must be representable, and
+    // counting them must not overflow
+    let span = Some(high - low)
+        .filter(|difference| difference.is_finite() && *difference < MAX_SPAN)
+        .and_then(|difference| (difference as usize).checked_add(1))
+        .ok_or_else(|| {
+            format!(
+                "random_int expects at most 2^53 numbers between low and high but got {} and {}.",
+                args[0], args[1]
+            )
+        })?;
+    let offset = context.rng.next_index(span);
+    Ok(Value::Number(RoxNumber(low + offset as f64)))
+}
+
+/// Shuffles a list in place.
+fn shuffle(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let list = list_arg("shuffle", &args[0])?;
+    list.check_mutable()?;
+
+    // Fisher-Yates
+    for i in (1..list.length()).rev() {
+        let j = context.rng.next_index(i + 1);
+        let (a, b) = (list.get(i), list.get(j));
+        if let (Some(a), Some(b)) = (a, b) {
+            list.set(i, b);
+            list.set(j, a);
+        }
+    }
+
+    Ok(Value::Nil)
+}
+
+fn choice(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let list = list_arg("choice", &args[0])?;
+    if list.length() == 0 {
+        return Err(String::from("choice expects a non-empty list."));
+    }
+
+    let index = context.rng.next_index(list.length());
+    Ok(list.get(index).unwrap_or_default())
+}
+
+fn seed(context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let seed = number_arg("seed", &args[0])?;
+    if seed.fract() != 0.0 || seed < 0.0 {
+        return Err(format!(
+            "seed expects a non-negative whole number but got {}.",
+            args[0]
+        ));
+    }
+
+    context.rng.seed(seed as u64);
+    Ok(Value::Nil)
+}
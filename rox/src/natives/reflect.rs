@@ -0,0 +1,133 @@
+use super::string_arg;
+use crate::{
+    NativeContext, ObjectType, RoxDict, RoxList, RoxNative, RoxNumber, RoxObject, RoxString,
+    RoxType, Value,
+};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("type_of", 1, type_of),
+        RoxNative::new("fields", 1, fields),
+        RoxNative::new("has_field", 2, has_field),
+        RoxNative::new("get_field", 2, get_field),
+        RoxNative::new("set_field", 3, set_field),
+        RoxNative::new("arity", 1, arity),
+        RoxNative::new("freeze", 1, freeze),
+        RoxNative::new("is_frozen", 1, is_frozen),
+    ]
+}
+
+fn map_arg(name: &str, value: &Value) -> Result<RoxDict, String> {
+    match value {
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjDict(dict),
+        }) => Ok(dict.clone()),
+        _ => Err(format!("{} expects a map but got {}.", name, value)),
+    }
+}
+
+/// Names the type of a value, using the same names as
+/// type annotations.
+fn type_of(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let rox_type = RoxType::of_value(&args[0]).to_string();
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+        RoxString::new(&rox_type),
+    ))))
+}
+
+/// Lists the keys of a map in sorted order.
+fn fields(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let dict = map_arg("fields", &args[0])?;
+    let keys = dict
+        .sorted_keys()
+        .into_iter()
+        .map(|key| Value::Object(RoxObject::new(ObjectType::ObjString(key))))
+        .collect();
+
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+        RoxList::new(keys),
+    ))))
+}
+
+fn has_field(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let dict = map_arg("has_field", &args[0])?;
+    let field = string_arg("has_field", &args[1])?;
+    Ok(Value::Boolean(dict.get(&field).is_some()))
+}
+
+fn get_field(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let dict = map_arg("get_field", &args[0])?;
+    let field = string_arg("get_field", &args[1])?;
+    dict.get(&field)
+        .ok_or_else(|| format!("Undefined property '{}'.", field))
+}
+
+fn set_field(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let dict = map_arg("set_field", &args[0])?;
+    let field = string_arg("set_field", &args[1])?;
+    dict.check_mutable()?;
+    dict.set(&field, &args[2]);
+    Ok(args[2].clone())
+}
+
+fn arity(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    match &args[0] {
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjNative(native),
+        }) => Ok(Value::Number(RoxNumber(native.arity as f64))),
+        value => Err(format!("arity expects a function but got {}.", value)),
+    }
+}
+
+/// Makes a list, map or set read-only, along with every list, map
+/// and set reachable from it, and returns the value.
+fn freeze(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    freeze_value(&args[0]);
+    Ok(args[0].clone())
+}
+
+fn freeze_value(value: &Value) {
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    // values that are already frozen have had their contents
+    // frozen too, which also stops at cycles
+    match &object.object_type {
+        ObjectType::ObjList(list) if !list.is_frozen() => {
+            list.freeze();
+            list.values().iter().for_each(freeze_value);
+        }
+        ObjectType::ObjDict(dict) if !dict.is_frozen() => {
+            dict.freeze();
+            dict.table()
+                .iter()
+                .for_each(|(_, value)| freeze_value(value));
+        }
+        ObjectType::ObjSet(set) if !set.is_frozen() => {
+            set.freeze();
+            set.sorted_values().iter().for_each(freeze_value);
+        }
+        ObjectType::ObjTuple(tuple) => tuple.values().iter().for_each(freeze_value),
+        ObjectType::ObjEnumVariant(variant) => variant.payload.iter().for_each(freeze_value),
+        _ => (),
+    }
+}
+
+/// Checks whether a value can be changed. Only lists, maps and
+/// sets can be, until they are frozen.
+fn is_frozen(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let frozen = match &args[0] {
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjList(list),
+        }) => list.is_frozen(),
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjDict(dict),
+        }) => dict.is_frozen(),
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjSet(set),
+        }) => set.is_frozen(),
+        _ => true,
+    };
+    Ok(Value::Boolean(frozen))
+}
@@ -0,0 +1,309 @@
+use super::{number_arg, string_arg};
+use crate::{
+    NativeContext, ObjectType, RoxDict, RoxNative, RoxNumber, RoxObject, RoxString, Value,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub fn natives() -> Vec<RoxNative> {
+    vec![
+        RoxNative::new("clock", 0, monotonic),
+        RoxNative::new("monotonic", 0, monotonic),
+        RoxNative::new("now", 0, now),
+        RoxNative::new("sleep", 1, sleep),
+        RoxNative::new("parse_time", 1, parse_time),
+        RoxNative::new("format_time", 2, format_time),
+        RoxNative::new("time_parts", 2, time_parts),
+    ]
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A calendar date and time in the proleptic Gregorian calendar,
+/// with an offset from UTC in minutes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: f64,
+    offset: i64,
+}
+
+impl DateTime {
+    /// Splits an epoch timestamp into its calendar parts as seen
+    /// from the given offset. The timestamp must be finite and its
+    /// local time must fit in a whole number of i64 seconds.
+    fn from_timestamp(timestamp: f64, offset: i64) -> Result<DateTime, String> {
+        let local = timestamp + (offset * 60) as f64;
+        if !local.is_finite() || local < i64::MIN as f64 || local >= i64::MAX as f64 {
+            return Err(format!("Timestamp {} is out of range.", timestamp));
+        }
+
+        let whole = local.floor();
+        let days = (whole as i64).div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = (whole as i64).rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        Ok(DateTime {
+            year,
+            month,
+            day,
+            hour: seconds_of_day / 3600,
+            minute: seconds_of_day % 3600 / 60,
+            second: (seconds_of_day % 60) as f64 + (local - whole),
+            offset,
+        })
+    }
+
+    fn timestamp(&self) -> f64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds =
+            days * SECONDS_PER_DAY + self.hour * 3600 + self.minute * 60 - self.offset * 60;
+        seconds as f64 + self.second
+    }
+
+    /// Parses `YYYY-MM-DD`, optionally followed by `THH:MM[:SS[.fff]]`
+    /// and a `Z` or `+HH:MM` offset. A missing offset means UTC.
+    fn parse(text: &str) -> Result<DateTime, String> {
+        let invalid = || format!("Invalid ISO-8601 timestamp '{}'.", text);
+        let field = |part: Option<&str>, digits: usize| -> Result<i64, String> {
+            match part {
+                Some(part) if part.len() == digits && part.bytes().all(|b| b.is_ascii_digit()) => {
+                    part.parse::<i64>().map_err(|_| invalid())
+                }
+                _ => Err(invalid()),
+            }
+        };
+
+        let (date, time) = match text.find(['T', 't', ' ']) {
+            Some(split) => (&text[..split], Some(&text[split + 1..])),
+            None => (text, None),
+        };
+
+        let mut date_parts = date.split('-');
+        let year = field(date_parts.next(), 4)?;
+        let month = field(date_parts.next(), 2)?;
+        let day = field(date_parts.next(), 2)?;
+        if date_parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        let mut date_time = DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0.0,
+            offset: 0,
+        };
+
+        if let Some(time) = time {
+            let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+                Some(split) => (&time[..split], &time[split..]),
+                None => (time, ""),
+            };
+
+            let mut clock_parts = clock.splitn(3, ':');
+            date_time.hour = field(clock_parts.next(), 2)?;
+            date_time.minute = field(clock_parts.next(), 2)?;
+            if let Some(second) = clock_parts.next() {
+                let (whole, fraction) = match second.split_once('.') {
+                    Some((whole, fraction)) => (whole, Some(fraction)),
+                    None => (second, None),
+                };
+                date_time.second = field(Some(whole), 2)? as f64;
+                if let Some(fraction) = fraction {
+                    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                        return Err(invalid());
+                    }
+                    date_time.second += format!("0.{}", fraction)
+                        .parse::<f64>()
+                        .map_err(|_| invalid())?;
+                }
+            }
+
+            date_time.offset = match offset {
+                "" | "Z" | "z" => 0,
+                _ => {
+                    let sign = if offset.starts_with('-') { -1 } else { 1 };
+                    let mut offset_parts = offset[1..].split(':');
+                    let hours = field(offset_parts.next(), 2)?;
+                    let minutes = field(offset_parts.next(), 2)?;
+                    if offset_parts.next().is_some() || hours > 23 || minutes > 59 {
+                        return Err(invalid());
+                    }
+                    sign * (hours * 60 + minutes)
+                }
+            };
+        }
+
+        let valid = (1..=12).contains(&date_time.month)
+            && (1..=days_in_month(date_time.year, date_time.month)).contains(&date_time.day)
+            && date_time.hour < 24
+            && date_time.minute < 60
+            && date_time.second < 60.0;
+        if valid {
+            Ok(date_time)
+        } else {
+            Err(invalid())
+        }
+    }
+
+    fn format(&self) -> String {
+        let whole = self.second.floor();
+        let millis = ((self.second - whole) * 1000.0).round() as i64;
+
+        let mut text = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, whole as i64
+        );
+        if millis > 0 {
+            text.push_str(&format!(".{:03}", millis.min(999)));
+        }
+
+        if self.offset == 0 {
+            text.push('Z');
+        } else {
+            let sign = if self.offset < 0 { '-' } else { '+' };
+            let offset = self.offset.abs();
+            text.push_str(&format!("{}{:02}:{:02}", sign, offset / 60, offset % 60));
+        }
+        text
+    }
+
+    fn to_value(self) -> Value {
+        let dict = RoxDict::new();
+        let parts = [
+            ("year", self.year as f64),
+            ("month", self.month as f64),
+            ("day", self.day as f64),
+            ("hour", self.hour as f64),
+            ("minute", self.minute as f64),
+            ("second", self.second),
+            ("offset", self.offset as f64),
+            ("timestamp", self.timestamp()),
+        ];
+        for (name, value) in parts {
+            dict.set(&RoxString::new(name), &Value::Number(RoxNumber(value)));
+        }
+        Value::Object(RoxObject::new(ObjectType::ObjDict(dict)))
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Counts the days from 1970-01-01 to a date, following
+/// Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Reads an offset from UTC in minutes, where nil means UTC.
+fn offset_arg(name: &str, value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Nil => Ok(0),
+        _ => {
+            let offset = number_arg(name, value)?;
+            if offset.fract() != 0.0 || offset.abs() >= 24.0 * 60.0 {
+                return Err(format!(
+                    "{} expects an offset in whole minutes but got {}.",
+                    name, value
+                ));
+            }
+            Ok(offset as i64)
+        }
+    }
+}
+
+/// Seconds elapsed since the VM started, from a clock that
+/// never goes backwards.
+fn monotonic(context: &mut NativeContext, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(RoxNumber(
+        context.started.elapsed().as_secs_f64(),
+    )))
+}
+
+/// Seconds since the Unix epoch.
+fn now(_context: &mut NativeContext, _args: &[Value]) -> Result<Value, String> {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|error| format!("now failed: {}.", error))?;
+    Ok(Value::Number(RoxNumber(elapsed.as_secs_f64())))
+}
+
+fn sleep(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let millis = number_arg("sleep", &args[0])?;
+    if !millis.is_finite() || millis < 0.0 {
+        return Err(format!(
+            "sleep expects a non-negative number of milliseconds but got {}.",
+            args[0]
+        ));
+    }
+
+    let duration = Duration::try_from_secs_f64(millis / 1000.0)
+        .map_err(|_| format!("sleep cannot wait for {} milliseconds.", args[0]))?;
+    std::thread::sleep(duration);
+    Ok(Value::Nil)
+}
+
+/// Parses an ISO-8601 timestamp into a map of its parts, along
+/// with the epoch `timestamp` it refers to.
+fn parse_time(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let text = string_arg("parse_time", &args[0])?;
+    Ok(DateTime::parse(&text)?.to_value())
+}
+
+/// Formats an epoch timestamp as ISO-8601 at an offset in minutes.
+fn format_time(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let timestamp = number_arg("format_time", &args[0])?;
+    let offset = offset_arg("format_time", &args[1])?;
+    let text = DateTime::from_timestamp(timestamp, offset)?.format();
+    Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+        RoxString::new(&text),
+    ))))
+}
+
+/// Splits an epoch timestamp into the same map as `parse_time`.
+fn time_parts(_context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+    let timestamp = number_arg("time_parts", &args[0])?;
+    let offset = offset_arg("time_parts", &args[1])?;
+    Ok(DateTime::from_timestamp(timestamp, offset)?.to_value())
+}
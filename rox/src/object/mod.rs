@@ -1,6 +1,22 @@
+mod roxbigint;
+mod roxbytes;
+mod roxdict;
+mod roxenum;
+mod roxlist;
+mod roxnative;
+mod roxset;
 mod roxstring;
+mod roxtuple;
 
+pub use crate::object::roxbigint::RoxBigInt;
+pub use crate::object::roxbytes::RoxBytes;
+pub use crate::object::roxdict::RoxDict;
+pub use crate::object::roxenum::RoxEnumVariant;
+pub use crate::object::roxlist::RoxList;
+pub use crate::object::roxnative::{NativeFn, RoxNative};
+pub use crate::object::roxset::RoxSet;
 pub use crate::object::roxstring::RoxString;
+pub use crate::object::roxtuple::RoxTuple;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RoxObject {
@@ -22,12 +38,28 @@ impl std::fmt::Display for RoxObject {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ObjectType {
     ObjString(RoxString),
+    ObjEnumVariant(RoxEnumVariant),
+    ObjList(RoxList),
+    ObjDict(RoxDict),
+    ObjNative(RoxNative),
+    ObjBigInt(RoxBigInt),
+    ObjSet(RoxSet),
+    ObjTuple(RoxTuple),
+    ObjBytes(RoxBytes),
 }
 
 impl std::fmt::Display for ObjectType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ObjectType::ObjString(string) => write!(f, "str = {}", string),
+            ObjectType::ObjEnumVariant(variant) => write!(f, "variant = {}", variant),
+            ObjectType::ObjList(list) => write!(f, "list = {}", list),
+            ObjectType::ObjDict(dict) => write!(f, "map = {}", dict),
+            ObjectType::ObjNative(native) => write!(f, "native = {}", native),
+            ObjectType::ObjBigInt(int) => write!(f, "bigint = {}", int),
+            ObjectType::ObjSet(set) => write!(f, "set = {}", set),
+            ObjectType::ObjTuple(tuple) => write!(f, "tuple = {}", tuple),
+            ObjectType::ObjBytes(bytes) => write!(f, "bytes = {}", bytes),
         }
     }
 }
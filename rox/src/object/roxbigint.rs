@@ -0,0 +1,325 @@
+use std::cmp::Ordering;
+use std::ops;
+
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// An arbitrary-precision integer, written with an `n` suffix as
+/// in `123n`. The magnitude is kept in base 10^9 limbs, least
+/// significant first, so that printing needs no division. Zero is
+/// never negative, so derived equality compares values.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RoxBigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+impl RoxBigInt {
+    /// Parses decimal digits with an optional leading sign.
+    pub fn parse(text: &str) -> Option<RoxBigInt> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let limbs = digits
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |limb, digit| limb * 10 + (digit - b'0') as u32)
+            })
+            .collect();
+
+        Some(RoxBigInt::from_parts(negative, limbs))
+    }
+
+    /// Converts a whole number exactly, or returns None for
+    /// fractional, infinite and NaN numbers.
+    pub fn from_f64(num: f64) -> Option<RoxBigInt> {
+        if !num.is_finite() || num.fract() != 0.0 {
+            return None;
+        }
+        RoxBigInt::parse(&format!("{:.0}", num))
+    }
+
+    /// The closest number to this integer, which is infinite
+    /// when the integer is too large for a number.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Divides, truncating towards zero, or returns
+    /// None when dividing by zero.
+    pub fn checked_div(&self, other: &RoxBigInt) -> Option<RoxBigInt> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let quotient = RoxBigInt::div_magnitude(&self.limbs, &other.limbs);
+        Some(RoxBigInt::from_parts(
+            self.negative != other.negative,
+            quotient,
+        ))
+    }
+
+    /// Compares against a number exactly, without rounding
+    /// this integer. Only NaN is unordered.
+    pub fn cmp_f64(&self, num: f64) -> Option<Ordering> {
+        if num.is_nan() {
+            return None;
+        }
+        if num.is_infinite() {
+            return Some(if num > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+
+        let whole = RoxBigInt::from_f64(num.trunc())?;
+        let fraction = 0.0_f64.partial_cmp(&num.fract())?;
+        Some(self.cmp(&whole).then(fraction))
+    }
+
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> RoxBigInt {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        RoxBigInt {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+        a.len()
+            .cmp(&b.len())
+            .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+    }
+
+    fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+        let mut carry = 0;
+        for i in 0..a.len().max(b.len()) {
+            let limb = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+            sum.push((limb % BASE) as u32);
+            carry = limb / BASE;
+        }
+        if carry > 0 {
+            sum.push(carry as u32);
+        }
+        sum
+    }
+
+    /// Subtracts b from a, where a is at least as large as b.
+    fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut difference = Vec::with_capacity(a.len());
+        let mut borrow = 0;
+        for (i, limb) in a.iter().enumerate() {
+            let subtrahend = *b.get(i).unwrap_or(&0) as i64 + borrow;
+            let mut limb = *limb as i64 - subtrahend;
+            borrow = 0;
+            if limb < 0 {
+                limb += BASE as i64;
+                borrow = 1;
+            }
+            difference.push(limb as u32);
+        }
+        difference
+    }
+
+    fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut product = vec![0u64; a.len() + b.len()];
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0;
+            for (j, y) in b.iter().enumerate() {
+                let limb = product[i + j] + *x as u64 * *y as u64 + carry;
+                product[i + j] = limb % BASE;
+                carry = limb / BASE;
+            }
+            product[i + b.len()] += carry;
+        }
+        product.into_iter().map(|limb| limb as u32).collect()
+    }
+
+    /// Schoolbook long division, finding each quotient
+    /// limb with a binary search.
+    fn div_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut quotient = vec![0; a.len()];
+        let mut remainder: Vec<u32> = vec![];
+
+        for (i, limb) in a.iter().enumerate().rev() {
+            remainder.insert(0, *limb);
+            remainder = RoxBigInt::from_parts(false, remainder).limbs;
+
+            let (mut low, mut high) = (0, BASE - 1);
+            while low < high {
+                let mid = (low + high).div_ceil(2);
+                let multiple = RoxBigInt::mul_magnitude(b, &[mid as u32]);
+                if RoxBigInt::cmp_magnitude(&trimmed(multiple), &remainder) == Ordering::Greater {
+                    high = mid - 1;
+                } else {
+                    low = mid;
+                }
+            }
+
+            let multiple = trimmed(RoxBigInt::mul_magnitude(b, &[low as u32]));
+            remainder = trimmed(RoxBigInt::sub_magnitude(&remainder, &multiple));
+            quotient[i] = low as u32;
+        }
+
+        quotient
+    }
+}
+
+fn trimmed(limbs: Vec<u32>) -> Vec<u32> {
+    RoxBigInt::from_parts(false, limbs).limbs
+}
+
+impl Ord for RoxBigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => RoxBigInt::cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => RoxBigInt::cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for RoxBigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Neg for &RoxBigInt {
+    type Output = RoxBigInt;
+
+    fn neg(self) -> Self::Output {
+        RoxBigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+}
+
+impl ops::Add<&RoxBigInt> for &RoxBigInt {
+    type Output = RoxBigInt;
+
+    fn add(self, rhs: &RoxBigInt) -> Self::Output {
+        if self.negative == rhs.negative {
+            return RoxBigInt::from_parts(
+                self.negative,
+                RoxBigInt::add_magnitude(&self.limbs, &rhs.limbs),
+            );
+        }
+
+        match RoxBigInt::cmp_magnitude(&self.limbs, &rhs.limbs) {
+            Ordering::Less => RoxBigInt::from_parts(
+                rhs.negative,
+                RoxBigInt::sub_magnitude(&rhs.limbs, &self.limbs),
+            ),
+            _ => RoxBigInt::from_parts(
+                self.negative,
+                RoxBigInt::sub_magnitude(&self.limbs, &rhs.limbs),
+            ),
+        }
+    }
+}
+
+impl ops::Sub<&RoxBigInt> for &RoxBigInt {
+    type Output = RoxBigInt;
+
+    fn sub(self, rhs: &RoxBigInt) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl ops::Mul<&RoxBigInt> for &RoxBigInt {
+    type Output = RoxBigInt;
+
+    fn mul(self, rhs: &RoxBigInt) -> Self::Output {
+        RoxBigInt::from_parts(
+            self.negative != rhs.negative,
+            RoxBigInt::mul_magnitude(&self.limbs, &rhs.limbs),
+        )
+    }
+}
+
+impl std::fmt::Display for RoxBigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((most, rest)) = self.limbs.split_last() else {
+            return write!(f, "0");
+        };
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", most)?;
+        for limb in rest.iter().rev() {
+            write!(f, "{:09}", limb)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(text: &str) -> RoxBigInt {
+        RoxBigInt::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_print() {
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(
+            big("-1234567890123456789012").to_string(),
+            "-1234567890123456789012"
+        );
+        assert_eq!(RoxBigInt::parse("12a"), None);
+        assert_eq!(
+            RoxBigInt::from_f64(1e21).unwrap().to_string(),
+            "1000000000000000000000"
+        );
+        assert_eq!(RoxBigInt::from_f64(1.5), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("999999999999999999");
+        let b = big("-1000000000000000001");
+        assert_eq!((&a + &b).to_string(), "-2");
+        assert_eq!((&a - &b).to_string(), "2000000000000000000");
+        assert_eq!(
+            (&a * &b).to_string(),
+            "-999999999999999999999999999999999999"
+        );
+        assert_eq!(
+            (&a * &b).checked_div(&a).unwrap().to_string(),
+            "-1000000000000000001"
+        );
+        assert_eq!(big("-7").checked_div(&big("2")).unwrap().to_string(), "-3");
+        assert_eq!(a.checked_div(&big("0")), None);
+    }
+
+    #[test]
+    fn test_comparison() {
+        assert!(big("-10") < big("-9"));
+        assert!(big("100000000000") > big("99999999999"));
+        assert_eq!(big("3").cmp_f64(3.5), Some(Ordering::Less));
+        assert_eq!(big("-3").cmp_f64(-3.5), Some(Ordering::Greater));
+        assert_eq!(big("3").cmp_f64(3.0), Some(Ordering::Equal));
+        assert_eq!(big("3").cmp_f64(f64::NAN), None);
+    }
+}
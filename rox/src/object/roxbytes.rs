@@ -0,0 +1,174 @@
+use crate::natives::string_value;
+use crate::{RoxNumber, Value};
+use std::rc::Rc;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// An immutable buffer of bytes for binary data, written `b"..."`
+/// with ASCII text and `\xNN` escapes, as in `b"\x89PNG"`, which is
+/// also how it prints. Indexing a buffer gives numbers from 0 to 255.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RoxBytes(Rc<Vec<u8>>);
+
+impl RoxBytes {
+    pub fn new(bytes: Vec<u8>) -> RoxBytes {
+        RoxBytes(Rc::new(bytes))
+    }
+
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Parses pairs of hex digits, ignoring whitespace between pairs.
+    pub fn from_hex(text: &str) -> Result<RoxBytes, String> {
+        let digits = text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                c.to_digit(16)
+                    .ok_or_else(|| format!("Invalid hex digit '{}'.", c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if digits.len() % 2 != 0 {
+            return Err(String::from("Hex string has an odd number of digits."));
+        }
+
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+            .collect();
+        Ok(RoxBytes::new(bytes))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Decodes standard base64, where the trailing padding
+    /// may be left out.
+    pub fn from_base64(text: &str) -> Result<RoxBytes, String> {
+        let text = text.trim_end_matches('=');
+        let sextets = text
+            .chars()
+            .map(|c| {
+                BASE64_ALPHABET
+                    .iter()
+                    .position(|&letter| letter as char == c)
+                    .map(|sextet| sextet as u32)
+                    .ok_or_else(|| format!("Invalid base64 character '{}'.", c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if sextets.len() % 4 == 1 {
+            return Err(String::from("Base64 string has an invalid length."));
+        }
+
+        let mut bytes = vec![];
+        for group in sextets.chunks(4) {
+            let bits = group
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (i, sextet)| bits | sextet << (18 - 6 * i));
+            let count = group.len() - 1;
+            bytes.extend(bits.to_be_bytes()[1..=count].iter());
+        }
+        Ok(RoxBytes::new(bytes))
+    }
+
+    pub fn to_base64(&self) -> String {
+        let mut text = String::new();
+        for group in self.0.chunks(3) {
+            let bits = group.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+                bits | (*byte as u32) << (16 - 8 * i)
+            });
+
+            for i in 0..4 {
+                if i <= group.len() {
+                    let sextet = (bits >> (18 - 6 * i)) & 0x3f;
+                    text.push(BASE64_ALPHABET[sextet as usize] as char);
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+        text
+    }
+
+    /// Calls the built-in bytes method called name, as in
+    /// `data.to_hex()`.
+    pub fn invoke(&self, name: &str, args: &[Value]) -> Result<Value, String> {
+        if !matches!(name, "len" | "to_hex" | "to_base64" | "decode") {
+            return Err(format!("Undefined bytes method '{}'.", name));
+        }
+        if !args.is_empty() {
+            return Err(format!(
+                "{} expects 0 arguments but got {}.",
+                name,
+                args.len()
+            ));
+        }
+
+        match name {
+            "len" => Ok(Value::Number(RoxNumber(self.length() as f64))),
+            "to_hex" => Ok(string_value(self.to_hex())),
+            "to_base64" => Ok(string_value(self.to_base64())),
+            _ => match std::str::from_utf8(&self.0) {
+                Ok(text) => Ok(string_value(text.to_string())),
+                Err(error) => Err(format!("Cannot decode bytes as UTF-8: {}.", error)),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for RoxBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "b\"")?;
+        for byte in self.0.iter() {
+            match byte {
+                b'"' => write!(f, "\\\"")?,
+                b'\\' => write!(f, "\\\\")?,
+                0x20..=0x7e => write!(f, "{}", *byte as char)?,
+                _ => write!(f, "\\x{:02x}", byte)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = RoxBytes::from_hex("00 ff 7A").unwrap();
+        assert_eq!(bytes.bytes(), &[0x00, 0xff, 0x7a]);
+        assert_eq!(bytes.to_hex(), "00ff7a");
+        assert!(RoxBytes::from_hex("abc").is_err());
+        assert!(RoxBytes::from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        for (text, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            let bytes = RoxBytes::new(text.as_bytes().to_vec());
+            assert_eq!(bytes.to_base64(), encoded);
+            assert_eq!(RoxBytes::from_base64(encoded).unwrap(), bytes);
+        }
+        assert_eq!(RoxBytes::from_base64("Zg").unwrap().bytes(), b"f");
+        assert!(RoxBytes::from_base64("Z").is_err());
+        assert!(RoxBytes::from_base64("Z!==").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{compare_once, display_once, RoxMap, RoxString, Table, Value};
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::Rc;
+
+/// A map from string keys to values, backed by the crate's hashtable.
+/// Like lists, maps are shared by reference and may be frozen.
+#[derive(Clone, Debug)]
+pub struct RoxDict {
+    table: Rc<RefCell<Table<RoxString, Value>>>,
+    frozen: Rc<Cell<bool>>,
+}
+
+impl RoxDict {
+    pub fn new() -> RoxDict {
+        RoxDict {
+            table: Rc::new(RefCell::new(Table::new())),
+            frozen: Rc::new(Cell::new(false)),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.table.borrow().len()
+    }
+
+    pub fn get(&self, key: &RoxString) -> Option<Value> {
+        self.table.borrow().get(key).cloned()
+    }
+
+    pub fn set(&self, key: &RoxString, value: &Value) {
+        self.table.borrow_mut().set(key, value);
+    }
+
+    pub fn table(&self) -> Ref<'_, Table<RoxString, Value>> {
+        self.table.borrow()
+    }
+
+    pub fn freeze(&self) {
+        self.frozen.set(true);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+
+    /// Fails with a message when the map is frozen, to be
+    /// checked before any change made on behalf of a script.
+    pub fn check_mutable(&self) -> Result<(), String> {
+        match self.is_frozen() {
+            true => Err(String::from("Cannot modify a frozen map.")),
+            false => Ok(()),
+        }
+    }
+
+    /// Identifies the map shared by all copies of this value.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.table) as usize
+    }
+
+    /// Returns the keys of the map in sorted order, so that
+    /// maps always print and iterate the same way.
+    pub fn sorted_keys(&self) -> Vec<RoxString> {
+        let mut keys = self
+            .table()
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+}
+
+impl Default for RoxDict {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for RoxDict {
+    fn eq(&self, other: &Self) -> bool {
+        compare_once(self.address(), other.address(), true, || {
+            *self.table.borrow() == *other.table.borrow()
+        })
+    }
+}
+
+impl Eq for RoxDict {}
+
+impl std::fmt::Display for RoxDict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_once(f, self.address(), "{...}", |f| {
+            let entries = self
+                .sorted_keys()
+                .iter()
+                .map(|key| format!("\"{}\": {}", key, self.get(key).unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write!(f, "{{{}}}", entries)
+        })
+    }
+}
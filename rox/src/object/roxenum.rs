@@ -0,0 +1,53 @@
+use crate::{RoxString, Value};
+
+/// A value of an enum declared with `enum Name { ... }`.
+/// Variants are tagged by their enum and variant names and
+/// may carry an optional list of payload values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoxEnumVariant {
+    pub enum_name: RoxString,
+    pub variant: RoxString,
+    pub payload: Vec<Value>,
+}
+
+impl RoxEnumVariant {
+    pub fn new(enum_name: &str, variant: &str) -> RoxEnumVariant {
+        RoxEnumVariant {
+            enum_name: RoxString::new(enum_name),
+            variant: RoxString::new(variant),
+            payload: vec![],
+        }
+    }
+
+    /// Checks that two variants share the same enum and variant
+    /// tag, ignoring any payload values.
+    pub fn same_tag(&self, other: &RoxEnumVariant) -> bool {
+        self.enum_name == other.enum_name && self.variant == other.variant
+    }
+
+    pub fn with_payload(&self, payload: Vec<Value>) -> RoxEnumVariant {
+        RoxEnumVariant {
+            enum_name: self.enum_name.clone(),
+            variant: self.variant.clone(),
+            payload,
+        }
+    }
+}
+
+impl std::fmt::Display for RoxEnumVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.enum_name, self.variant)?;
+
+        if !self.payload.is_empty() {
+            let payload = self
+                .payload
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, "({})", payload)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{compare_once, display_once, Value};
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::Rc;
+
+/// A growable list of values. Lists are shared by reference, so
+/// copies of a list value all observe mutations made through any of them.
+/// Freezing a list also applies to every copy.
+#[derive(Clone, Debug, Default)]
+pub struct RoxList {
+    values: Rc<RefCell<Vec<Value>>>,
+    frozen: Rc<Cell<bool>>,
+}
+
+impl RoxList {
+    pub fn new(values: Vec<Value>) -> RoxList {
+        RoxList {
+            values: Rc::new(RefCell::new(values)),
+            frozen: Rc::new(Cell::new(false)),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.values.borrow().len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Value> {
+        self.values.borrow().get(index).cloned()
+    }
+
+    /// Overwrites the value at index, returning false
+    /// if the index is out of bounds.
+    pub fn set(&self, index: usize, value: Value) -> bool {
+        match self.values.borrow_mut().get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn push(&self, value: Value) {
+        self.values.borrow_mut().push(value);
+    }
+
+    /// Copies the values from index start to the end of
+    /// the list into a new list.
+    pub fn slice_from(&self, start: usize) -> RoxList {
+        let values = self.values.borrow();
+        RoxList::new(values.get(start..).unwrap_or_default().to_vec())
+    }
+
+    pub fn values(&self) -> Ref<'_, Vec<Value>> {
+        self.values.borrow()
+    }
+
+    pub fn freeze(&self) {
+        self.frozen.set(true);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+
+    /// Fails with a message when the list is frozen, to be
+    /// checked before any change made on behalf of a script.
+    pub fn check_mutable(&self) -> Result<(), String> {
+        match self.is_frozen() {
+            true => Err(String::from("Cannot modify a frozen list.")),
+            false => Ok(()),
+        }
+    }
+
+    /// Identifies the list shared by all copies of this value.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.values) as usize
+    }
+}
+
+impl PartialEq for RoxList {
+    fn eq(&self, other: &Self) -> bool {
+        compare_once(self.address(), other.address(), true, || {
+            *self.values.borrow() == *other.values.borrow()
+        })
+    }
+}
+
+impl Eq for RoxList {}
+
+impl std::fmt::Display for RoxList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_once(f, self.address(), "[...]", |f| {
+            let values = self
+                .values()
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write!(f, "[{}]", values)
+        })
+    }
+}
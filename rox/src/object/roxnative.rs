@@ -0,0 +1,99 @@
+use crate::{NativeContext, Value};
+
+/// The signature of a function implemented in Rust. Natives receive
+/// the state of their VM and their arguments in call order, and report
+/// failures as a message which the VM turns into a runtime error.
+pub type NativeFn = fn(&mut NativeContext, &[Value]) -> Result<Value, String>;
+
+/// A built-in function exposed to scripts as a global variable.
+#[derive(Clone)]
+pub struct RoxNative {
+    pub name: &'static str,
+    pub arity: usize,
+    pub optional: usize,
+    pub variadic: bool,
+    pub function: NativeFn,
+}
+
+impl RoxNative {
+    pub fn new(name: &'static str, arity: usize, function: NativeFn) -> RoxNative {
+        RoxNative::with_optional(name, arity, 0, function)
+    }
+
+    /// Creates a native whose last `optional` parameters may be left
+    /// out of a call, in which case the function receives nil for them.
+    pub fn with_optional(
+        name: &'static str,
+        arity: usize,
+        optional: usize,
+        function: NativeFn,
+    ) -> RoxNative {
+        RoxNative {
+            name,
+            arity,
+            optional,
+            variadic: false,
+            function,
+        }
+    }
+
+    /// Creates a native that takes any number of arguments, and
+    /// receives them as they were passed.
+    pub fn variadic(name: &'static str, function: NativeFn) -> RoxNative {
+        RoxNative {
+            name,
+            arity: 0,
+            optional: 0,
+            variadic: true,
+            function,
+        }
+    }
+
+    pub fn call(&self, context: &mut NativeContext, args: &[Value]) -> Result<Value, String> {
+        if self.variadic {
+            return (self.function)(context, args);
+        }
+
+        let required = self.arity - self.optional;
+        if args.len() > self.arity || args.len() < required {
+            let expected = match self.optional {
+                0 => self.arity.to_string(),
+                _ => format!("{} to {}", required, self.arity),
+            };
+            return Err(format!(
+                "{} expects {} arguments but got {}.",
+                self.name,
+                expected,
+                args.len()
+            ));
+        }
+
+        if args.len() < self.arity {
+            let mut args = args.to_vec();
+            args.resize(self.arity, Value::Nil);
+            return (self.function)(context, &args);
+        }
+
+        (self.function)(context, args)
+    }
+}
+
+impl std::fmt::Debug for RoxNative {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RoxNative({}/{})", self.name, self.arity)
+    }
+}
+
+impl PartialEq for RoxNative {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.arity == other.arity
+    }
+}
+
+impl Eq for RoxNative {}
+
+impl std::fmt::Display for RoxNative {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
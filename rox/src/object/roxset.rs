@@ -0,0 +1,215 @@
+use crate::{ObjectType, RoxList, RoxMap, RoxNumber, RoxObject, Table, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// An unordered collection of distinct values, backed by the crate's
+/// hashtable. Values are distinct when they are not equal with `==`,
+/// so `1` and `1n` are the same element. Elements are hashed by their
+/// contents, so they must be values that cannot change, such as
+/// tuples and frozen lists. Like lists and maps, sets are shared by
+/// reference and may be frozen.
+#[derive(Clone, Debug)]
+pub struct RoxSet {
+    table: Rc<RefCell<Table<Element, ()>>>,
+    frozen: Rc<Cell<bool>>,
+}
+
+/// A set element with its hash computed up front, so that the table
+/// never hashes a value while it is borrowed.
+#[derive(Clone, Debug)]
+struct Element {
+    hash: u64,
+    value: Value,
+}
+
+impl Element {
+    fn new(value: &Value) -> Element {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        Element {
+            hash: hasher.finish(),
+            value: value.clone(),
+        }
+    }
+}
+
+impl Hash for Element {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for Element {}
+
+impl RoxSet {
+    /// Creates a set of the distinct values, failing if
+    /// any of them could change after being added.
+    pub fn new(values: &[Value]) -> Result<RoxSet, String> {
+        let set = RoxSet {
+            table: Rc::new(RefCell::new(Table::new())),
+            frozen: Rc::new(Cell::new(false)),
+        };
+        for value in values {
+            set.add(value)?;
+        }
+        Ok(set)
+    }
+
+    pub fn length(&self) -> usize {
+        self.table.borrow().len()
+    }
+
+    pub fn has(&self, value: &Value) -> bool {
+        let element = Element::new(value);
+        self.table.borrow().contains(element)
+    }
+
+    /// Adds value, returning false if an equal value was already in
+    /// the set, which keeps the value it was first added as. Values
+    /// that could change while in the set, such as lists that are not
+    /// frozen, are rejected.
+    pub fn add(&self, value: &Value) -> Result<bool, String> {
+        if !value.is_immutable() {
+            return Err(format!(
+                "Cannot add {} to a set because it can change. Freeze it or use a tuple.",
+                value
+            ));
+        }
+
+        let element = Element::new(value);
+        let mut table = self.table.borrow_mut();
+        if table.contains(element.clone()) {
+            return Ok(false);
+        }
+        table.set(&element, &());
+        Ok(true)
+    }
+
+    /// Removes value, returning false if it was not in the set.
+    pub fn remove(&self, value: &Value) -> bool {
+        let element = Element::new(value);
+        self.table.borrow_mut().remove(element).is_some()
+    }
+
+    /// Returns the values of the set ordered by `Value::total_cmp`,
+    /// so that sets always print and iterate the same way.
+    pub fn sorted_values(&self) -> Vec<Value> {
+        let mut values = self
+            .table
+            .borrow()
+            .iter()
+            .map(|(element, _)| element.value.clone())
+            .collect::<Vec<_>>();
+        values.sort_by(Value::total_cmp);
+        values
+    }
+
+    pub fn freeze(&self) {
+        self.frozen.set(true);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+
+    /// Identifies the set shared by all copies of this value.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.table) as usize
+    }
+
+    pub fn union(&self, other: &RoxSet) -> Result<RoxSet, String> {
+        let mut values = self.sorted_values();
+        values.extend(other.sorted_values());
+        RoxSet::new(&values)
+    }
+
+    pub fn intersection(&self, other: &RoxSet) -> Result<RoxSet, String> {
+        let values = self.sorted_values();
+        let shared = values.iter().filter(|value| other.has(value));
+        RoxSet::new(&shared.cloned().collect::<Vec<_>>())
+    }
+
+    pub fn difference(&self, other: &RoxSet) -> Result<RoxSet, String> {
+        let values = self.sorted_values();
+        let remaining = values.iter().filter(|value| !other.has(value));
+        RoxSet::new(&remaining.cloned().collect::<Vec<_>>())
+    }
+
+    /// Calls the built-in set method called name, as in
+    /// `seen.add(item)`.
+    pub fn invoke(&self, name: &str, args: &[Value]) -> Result<Value, String> {
+        let arity = match name {
+            "len" | "values" => 0,
+            "add" | "remove" | "has" | "union" | "intersection" | "difference" => 1,
+            _ => return Err(format!("Undefined set method '{}'.", name)),
+        };
+
+        if args.len() != arity {
+            return Err(format!(
+                "{} expects {} arguments but got {}.",
+                name,
+                arity,
+                args.len()
+            ));
+        }
+
+        match name {
+            "len" => Ok(Value::Number(RoxNumber(self.length() as f64))),
+            "values" => Ok(Value::Object(RoxObject::new(ObjectType::ObjList(
+                RoxList::new(self.sorted_values()),
+            )))),
+            "add" | "remove" if self.is_frozen() => {
+                Err(String::from("Cannot modify a frozen set."))
+            }
+            "add" => Ok(Value::Boolean(self.add(&args[0])?)),
+            "remove" => Ok(Value::Boolean(self.remove(&args[0]))),
+            "has" => Ok(Value::Boolean(self.has(&args[0]))),
+            "union" => Ok(set_value(self.union(&set_arg(name, &args[0])?)?)),
+            "intersection" => Ok(set_value(self.intersection(&set_arg(name, &args[0])?)?)),
+            "difference" => Ok(set_value(self.difference(&set_arg(name, &args[0])?)?)),
+            _ => unreachable!("Set method arity table is out of sync for '{}'", name),
+        }
+    }
+}
+
+fn set_value(set: RoxSet) -> Value {
+    Value::Object(RoxObject::new(ObjectType::ObjSet(set)))
+}
+
+fn set_arg(method: &str, value: &Value) -> Result<RoxSet, String> {
+    match value {
+        Value::Object(RoxObject {
+            object_type: ObjectType::ObjSet(set),
+        }) => Ok(set.clone()),
+        _ => Err(format!("{} expects a set but got {}.", method, value)),
+    }
+}
+
+impl PartialEq for RoxSet {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.table, &other.table) || *self.table.borrow() == *other.table.borrow()
+    }
+}
+
+impl Eq for RoxSet {}
+
+impl std::fmt::Display for RoxSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = self
+            .sorted_values()
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "set([{}])", values)
+    }
+}
@@ -1,8 +1,13 @@
+use crate::natives::{string_arg, string_value};
+use crate::{ObjectType, RoxBytes, RoxList, RoxNumber, RoxObject, Value};
 use std::convert::From;
 use std::hash::Hash;
 use std::ops::Deref;
 use std::rc::Rc;
 
+/// The longest string, in bytes, that `repeat` and `replace` will build.
+const MAX_REPEAT_LENGTH: usize = 1 << 30;
+
 #[derive(Clone, Debug, Eq)]
 pub struct RoxString(String);
 
@@ -26,6 +31,171 @@ impl RoxString {
     pub fn raw_parts(&mut self) -> (*const u8, usize, usize) {
         (self.0.as_ptr(), self.0.len(), self.0.capacity())
     }
+
+    /// The number of characters in the string, as opposed
+    /// to `length` which counts bytes.
+    pub fn char_count(&self) -> usize {
+        self.0.chars().count()
+    }
+
+    /// Returns the characters from index start up to but not including
+    /// end, or None if the range does not fit in the string.
+    pub fn substring(&self, start: usize, end: usize) -> Option<RoxString> {
+        if start > end || end > self.char_count() {
+            return None;
+        }
+
+        let substring = self
+            .0
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect::<String>();
+        Some(RoxString(substring))
+    }
+
+    /// Calls the built-in string method called name, as in
+    /// `"a,b".split(",")`.
+    pub fn invoke(&self, name: &str, args: &[Value]) -> Result<Value, String> {
+        let arity = match name {
+            "len" | "upper" | "lower" | "trim" | "chars" | "encode" => 0,
+            "split" | "join" | "find" | "starts_with" | "ends_with" | "repeat" => 1,
+            "replace" | "substring" => 2,
+            _ => return Err(format!("Undefined string method '{}'.", name)),
+        };
+
+        if args.len() != arity {
+            return Err(format!(
+                "{} expects {} arguments but got {}.",
+                name,
+                arity,
+                args.len()
+            ));
+        }
+
+        match name {
+            "len" => Ok(Value::Number(RoxNumber(self.char_count() as f64))),
+            "upper" => Ok(string_value(self.0.to_uppercase())),
+            "lower" => Ok(string_value(self.0.to_lowercase())),
+            "trim" => Ok(string_value(self.0.trim().to_string())),
+            "encode" => Ok(Value::Object(RoxObject::new(ObjectType::ObjBytes(
+                RoxBytes::new(self.0.as_bytes().to_vec()),
+            )))),
+            "chars" => Ok(list_value(
+                self.0
+                    .chars()
+                    .map(|c| string_value(c.to_string()))
+                    .collect(),
+            )),
+            "split" => {
+                // an empty separator splits the string into its characters
+                let separator = string_arg(name, &args[0])?;
+                if separator.is_empty() {
+                    return self.invoke("chars", &[]);
+                }
+
+                Ok(list_value(
+                    self.0
+                        .split(separator.0.as_str())
+                        .map(|part| string_value(part.to_string()))
+                        .collect(),
+                ))
+            }
+            "join" => match &args[0] {
+                Value::Object(RoxObject {
+                    object_type: ObjectType::ObjList(list),
+                }) => {
+                    let parts = list
+                        .values()
+                        .iter()
+                        .map(|value| match value {
+                            Value::Object(RoxObject {
+                                object_type: ObjectType::ObjString(string),
+                            }) => string.0.clone(),
+                            _ => value.to_string(),
+                        })
+                        .collect::<Vec<_>>();
+                    Ok(string_value(parts.join(&self.0)))
+                }
+                value => Err(format!("join expects a list but got {}.", value)),
+            },
+            "find" => {
+                let needle = string_arg(name, &args[0])?;
+                Ok(match self.0.find(needle.0.as_str()) {
+                    Some(byte_index) => {
+                        Value::Number(RoxNumber(self.0[..byte_index].chars().count() as f64))
+                    }
+                    None => Value::Nil,
+                })
+            }
+            "starts_with" => Ok(Value::Boolean(
+                self.0.starts_with(string_arg(name, &args[0])?.0.as_str()),
+            )),
+            "ends_with" => Ok(Value::Boolean(
+                self.0.ends_with(string_arg(name, &args[0])?.0.as_str()),
+            )),
+            "repeat" => {
+                let count = index_arg(name, &args[0])?;
+                match self.0.len().checked_mul(count) {
+                    Some(length) if length <= MAX_REPEAT_LENGTH => {
+                        Ok(string_value(self.0.repeat(count)))
+                    }
+                    _ => Err(format!(
+                        "repeat would build a string longer than {} bytes.",
+                        MAX_REPEAT_LENGTH
+                    )),
+                }
+            }
+            "replace" => {
+                let from = string_arg(name, &args[0])?;
+                let to = string_arg(name, &args[1])?;
+                // an empty pattern matches between every character
+                let count = self.0.matches(from.0.as_str()).count();
+                let length = count
+                    .checked_mul(to.0.len())
+                    .and_then(|added| (self.0.len() - count * from.0.len()).checked_add(added));
+                match length {
+                    Some(length) if length <= MAX_REPEAT_LENGTH => {
+                        Ok(string_value(self.0.replace(from.0.as_str(), to.0.as_str())))
+                    }
+                    _ => Err(format!(
+                        "replace would build a string longer than {} bytes.",
+                        MAX_REPEAT_LENGTH
+                    )),
+                }
+            }
+            "substring" => {
+                let start = index_arg(name, &args[0])?;
+                let end = index_arg(name, &args[1])?;
+                match self.substring(start, end) {
+                    Some(substring) => Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+                        substring,
+                    )))),
+                    None => Err(format!(
+                        "Substring {}..{} out of bounds for string of length {}.",
+                        start,
+                        end,
+                        self.char_count()
+                    )),
+                }
+            }
+            _ => unreachable!("String method arity table is out of sync for '{}'", name),
+        }
+    }
+}
+
+fn list_value(values: Vec<Value>) -> Value {
+    Value::Object(RoxObject::new(ObjectType::ObjList(RoxList::new(values))))
+}
+
+fn index_arg(method: &str, value: &Value) -> Result<usize, String> {
+    match value {
+        Value::Number(num) if num.fract() == 0.0 && **num >= 0.0 => Ok(**num as usize),
+        _ => Err(format!(
+            "{} expects a non-negative integer but got {}.",
+            method, value
+        )),
+    }
 }
 
 impl Hash for RoxString {
@@ -41,12 +211,30 @@ impl PartialEq for RoxString {
     }
 }
 
+impl PartialOrd for RoxString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RoxString {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
 impl From<Rc<String>> for RoxString {
     fn from(str_rc: Rc<String>) -> Self {
         RoxString(str_rc.to_string())
     }
 }
 
+impl From<String> for RoxString {
+    fn from(s: String) -> Self {
+        RoxString(s)
+    }
+}
+
 impl From<&str> for RoxString {
     fn from(s: &str) -> Self {
         RoxString(Rc::new(s.to_string()).to_string())
@@ -0,0 +1,42 @@
+use crate::Value;
+use std::rc::Rc;
+
+/// An immutable sequence of values, written `(a, b, c)`. A tuple of
+/// one value needs a trailing comma, as in `(a,)`, to tell it apart
+/// from a grouped expression.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RoxTuple(Rc<Vec<Value>>);
+
+impl RoxTuple {
+    pub fn new(values: Vec<Value>) -> RoxTuple {
+        RoxTuple(Rc::new(values))
+    }
+
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Value> {
+        self.0.get(index).cloned()
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for RoxTuple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = self
+            .values()
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.length() {
+            1 => write!(f, "({},)", values),
+            _ => write!(f, "({})", values),
+        }
+    }
+}
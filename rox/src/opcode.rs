@@ -1,3 +1,5 @@
+use crate::RoxType;
+
 /**
  * The OpCodes for the Chunk instructions
  * to be used for the Rox VM
@@ -28,6 +30,31 @@ pub enum OpCode {
     OpJumpIfFalse(Option<usize>),
     OpJump(Option<usize>),
     OpLoop(usize),
+    OpDup,
+    OpVariant(usize, usize), // index of the variant tag constant and its payload arity
+    OpMatchVariant(usize),   // index of the variant tag constant to test against
+    OpMatchRange(usize, usize), // indices of the lower (inclusive) and upper (exclusive) bounds
+    OpBindPayload(usize),
+    OpUnbind(usize),
+    OpGetBinding(usize),
+    OpMatchError,
+    OpBuildList(usize), // number of elements to pop off the stack
+    OpBuildMap(usize),  // number of key-value pairs to pop off the stack
+    OpBuildTuple(usize),
+    OpGetIndex,
+    OpSetIndex,
+    OpCheckLength(usize, bool), // number of destructuring targets and whether a rest target follows
+    OpListRest(usize),
+    OpCheckType(RoxType),
+    OpGetProperty(usize), // stores the index of the property name in the constants array
+    OpSetProperty(usize),
+    OpJumpIfNil(Option<usize>),
+    OpCall(usize),          // stores the number of arguments
+    OpInvoke(usize, usize), // stores the method name index and the number of arguments
+    OpGetSlice,
+    OpAssert,
+    OpTest(usize, Option<usize>), // stores the test name index and the offset past its body
+    OpEndTest,
 }
 
 impl std::fmt::Display for OpCode {
@@ -57,6 +84,31 @@ impl std::fmt::Display for OpCode {
             OpCode::OpJumpIfFalse(_) => write!(f, "OP_JUMP_IF_FALSE"),
             OpCode::OpJump(_) => write!(f, "OP_JUMP"),
             OpCode::OpLoop(_) => write!(f, "OP_LOOP"),
+            OpCode::OpDup => write!(f, "OP_DUP"),
+            OpCode::OpVariant(..) => write!(f, "OP_VARIANT"),
+            OpCode::OpMatchVariant(_) => write!(f, "OP_MATCH_VARIANT"),
+            OpCode::OpMatchRange(..) => write!(f, "OP_MATCH_RANGE"),
+            OpCode::OpBindPayload(_) => write!(f, "OP_BIND_PAYLOAD"),
+            OpCode::OpUnbind(_) => write!(f, "OP_UNBIND"),
+            OpCode::OpGetBinding(_) => write!(f, "OP_GET_BINDING"),
+            OpCode::OpMatchError => write!(f, "OP_MATCH_ERROR"),
+            OpCode::OpBuildList(_) => write!(f, "OP_BUILD_LIST"),
+            OpCode::OpBuildMap(_) => write!(f, "OP_BUILD_MAP"),
+            OpCode::OpBuildTuple(_) => write!(f, "OP_BUILD_TUPLE"),
+            OpCode::OpGetIndex => write!(f, "OP_GET_INDEX"),
+            OpCode::OpSetIndex => write!(f, "OP_SET_INDEX"),
+            OpCode::OpCheckLength(..) => write!(f, "OP_CHECK_LENGTH"),
+            OpCode::OpListRest(_) => write!(f, "OP_LIST_REST"),
+            OpCode::OpCheckType(_) => write!(f, "OP_CHECK_TYPE"),
+            OpCode::OpGetProperty(_) => write!(f, "OP_GET_PROPERTY"),
+            OpCode::OpSetProperty(_) => write!(f, "OP_SET_PROPERTY"),
+            OpCode::OpJumpIfNil(_) => write!(f, "OP_JUMP_IF_NIL"),
+            OpCode::OpCall(_) => write!(f, "OP_CALL"),
+            OpCode::OpInvoke(_, _) => write!(f, "OP_INVOKE"),
+            OpCode::OpGetSlice => write!(f, "OP_GET_SLICE"),
+            OpCode::OpAssert => write!(f, "OP_ASSERT"),
+            OpCode::OpTest(_, _) => write!(f, "OP_TEST"),
+            OpCode::OpEndTest => write!(f, "OP_END_TEST"),
         }
     }
 }
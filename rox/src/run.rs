@@ -15,7 +15,10 @@ use std::{fs, io};
 pub struct Config {
     vm: VM,
     filename: Option<String>,
+    test_files: Vec<String>,
+    integer_promotion: bool,
     pub is_repl: bool,
+    pub is_test: bool,
 }
 
 #[derive(Debug)]
@@ -46,27 +49,67 @@ impl std::fmt::Display for ConfigError {
 }
 
 impl Config {
-    pub fn new(args: &mut std::env::Args) -> Result<Config, ConfigError> {
+    /// Reads the command line `rox [--promote-ints] [file args... |
+    /// --test files...]`. `--promote-ints` turns on promotion of
+    /// whole number arithmetic to big integers when a result is too
+    /// large to be exact.
+    pub fn new<I>(args: &mut I) -> Result<Config, ConfigError>
+    where
+        I: Iterator<Item = String>,
+    {
         // skips the first cl arg
-        let num_args = args.len();
         args.next();
-        if num_args == 1 {
-            Ok(Config {
-                vm: VM::new(),
+
+        let mut vm = VM::new();
+        let mut integer_promotion = false;
+        let mut filename = args.next();
+
+        // options come before the file name
+        while filename.as_deref() == Some("--promote-ints") {
+            integer_promotion = true;
+            filename = args.next();
+        }
+        vm.set_integer_promotion(integer_promotion);
+
+        match filename.as_deref() {
+            None => Ok(Config {
+                vm,
                 filename: None,
+                test_files: vec![],
+                integer_promotion,
                 is_repl: true,
-            })
-        } else if num_args == 2 {
-            Ok(Config {
-                vm: VM::new(),
+                is_test: false,
+            }),
+            // a flag is used for tests so any file name can be run
+            Some("--test") => Ok(Config {
+                vm,
+                filename: None,
+                test_files: args.collect(),
+                integer_promotion,
                 is_repl: false,
-                filename: args.next(),
-            })
-        } else {
-            Err(ConfigError::new(String::from("Usage: rox [filename]")))
+                is_test: true,
+            }),
+            // arguments after the filename are passed on to the script
+            Some(_) => {
+                vm.set_script_args(args.collect());
+
+                Ok(Config {
+                    vm,
+                    filename,
+                    test_files: vec![],
+                    integer_promotion,
+                    is_repl: false,
+                    is_test: false,
+                })
+            }
         }
     }
 
+    /// The status the script asked the process to exit with.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.vm.exit_code()
+    }
+
     pub fn run_file_with_filename(&mut self, pathname: &str) -> Result<(), ConfigError> {
         // read the file contents into string
         let file_contents = match fs::read_to_string(pathname) {
@@ -115,6 +158,50 @@ impl Config {
         Ok(())
     }
 
+    /// Runs the test blocks of each file given to `rox --test`. Failures
+    /// go to stderr, apart from the script's own output, and the pass and
+    /// fail counts of every file go to stdout.
+    /// Returns whether all of the tests passed.
+    pub fn run_tests(&mut self) -> Result<bool, ConfigError> {
+        let mut all_passed = true;
+
+        for file in &self.test_files {
+            let file_contents = fs::read_to_string(file).map_err(|msg| {
+                ConfigError::new(format!(
+                    "Error reading from file {} with msg: {}",
+                    file, msg
+                ))
+            })?;
+
+            let mut vm = VM::new();
+            vm.set_test_mode(true);
+            vm.set_integer_promotion(self.integer_promotion);
+            let result = vm.interpret(&file_contents);
+
+            let mut passed = 0;
+            let mut failed = 0;
+            for test in vm.test_results() {
+                match &test.failure {
+                    Some(failure) => {
+                        failed += 1;
+                        eprintln!("FAIL {} \"{}\": {}", file, test.name, failure);
+                    }
+                    None => passed += 1,
+                }
+            }
+
+            if let Err(error) = result {
+                failed += 1;
+                eprintln!("ERROR {}: {}", file, error);
+            }
+
+            println!("{}: {} passed, {} failed", file, passed, failed);
+            all_passed &= failed == 0;
+        }
+
+        Ok(all_passed)
+    }
+
     pub fn repl(&mut self) {
         let mut repl = Repl::new();
         repl.screen_update(crate::repl::ScreenClear::All).unwrap();
@@ -161,7 +248,12 @@ impl Config {
                 .current()
                 .unwrap_or_else(|| panic!("Error getting input at current history"));
 
-            if let Err(val) = self.vm.interpret(input) {
+            let result = self.vm.interpret(input);
+            if let Some(code) = self.vm.exit_code() {
+                std::process::exit(code);
+            }
+
+            if let Err(val) = result {
                 panic!("Error: {val}");
                 //                screen_idx += 1;
                 //                writeln!(stdout, "{}", termion::cursor::Goto(1, screen_idx)).unwrap();
@@ -1,12 +1,18 @@
 use crate::{
     token::{Token, TokenType},
-    RoxNumber, RoxString, TokenStream, DEBUG_MODE,
+    RoxBigInt, RoxBytes, RoxNumber, RoxString, TokenStream, DEBUG_MODE,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::{iter::Peekable, str::CharIndices};
+use std::{
+    iter::{Enumerate, Peekable},
+    str::Chars,
+};
+use unicode_ident::{is_xid_continue, is_xid_start};
 
-type Peeker<'a> = Peekable<CharIndices<'a>>;
+// characters are indexed by position rather than by byte offset, so that
+// columns stay correct on lines containing multi-byte characters
+type Peeker<'a> = Peekable<Enumerate<Chars<'a>>>;
 
 #[derive(Default)]
 pub struct Scanner {
@@ -58,13 +64,110 @@ impl Scanner {
         TokenType::StringLiteral(Rc::new(RoxString::new(&result)))
     }
 
+    /// Scans a bytes literal such as `b"ab\x00"`. ASCII characters
+    /// stand for themselves, and any other byte is written as `\xNN`,
+    /// with `\\` and `\"` for a backslash and a quote.
+    fn bytes(peeker: &mut Peeker) -> TokenType {
+        peeker.next();
+
+        let mut bytes = vec![];
+        let mut error = None;
+        loop {
+            let c = match peeker.next() {
+                Some((_, '"')) => break,
+                Some((_, c)) => c,
+                None => return TokenType::Error(String::from("Unterminated bytes literal")),
+            };
+
+            let byte = match c {
+                '\\' => Scanner::bytes_escape(peeker),
+                c if c.is_ascii() => Ok(c as u8),
+                _ => Err(format!(
+                    "Bytes literals can only hold ASCII, use \\x escapes for '{}'.",
+                    c
+                )),
+            };
+
+            // the rest of the literal is still consumed after an error
+            match byte {
+                Ok(byte) => bytes.push(byte),
+                Err(message) => {
+                    error.get_or_insert(message);
+                }
+            }
+        }
+
+        match error {
+            Some(message) => TokenType::Error(message),
+            None => TokenType::Bytes(Rc::new(RoxBytes::new(bytes))),
+        }
+    }
+
+    /// Reads the escape after a backslash in a bytes literal.
+    fn bytes_escape(peeker: &mut Peeker) -> Result<u8, String> {
+        match peeker.next() {
+            Some((_, '\\')) => Ok(b'\\'),
+            Some((_, '"')) => Ok(b'"'),
+            Some((_, 'x')) => {
+                let mut digits = String::new();
+                while digits.len() < 2 {
+                    match peeker.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                        Some((_, c)) => digits.push(c),
+                        None => break,
+                    }
+                }
+
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) if digits.len() == 2 => Ok(byte),
+                    _ => Err(format!(
+                        "Invalid escape '\\x{}' in bytes literal, expected two hex digits.",
+                        digits
+                    )),
+                }
+            }
+            Some((_, c)) => Err(format!("Invalid escape '\\{}' in bytes literal.", c)),
+            None => Err(String::from("Unterminated bytes literal")),
+        }
+    }
+
     fn number(peeker: &mut Peeker, ch: &char) -> TokenType {
         let mut string_of_num = ch.to_string();
-        while let Some((_, c)) = peeker.next_if(|(_, c)| c.is_numeric() || *c == '.') {
+        while let Some((_, c)) = peeker.next_if(|(_, c)| c.is_numeric()) {
             string_of_num.push(c)
         }
 
-        match string_of_num.parse::<f32>() {
+        // only consume a '.' when it starts a fractional part, so that
+        // range patterns like `1..5` scan as two numbers
+        let mut lookahead = peeker.clone();
+        if let (Some((_, '.')), Some((_, c))) = (lookahead.next(), lookahead.next()) {
+            if c.is_numeric() {
+                peeker.next();
+                string_of_num.push('.');
+                while let Some((_, c)) = peeker.next_if(|(_, c)| c.is_numeric()) {
+                    string_of_num.push(c)
+                }
+            }
+        }
+
+        // a whole number suffixed with 'n' is a big integer literal
+        if !string_of_num.contains('.') {
+            let mut lookahead = peeker.clone();
+            let suffixed = matches!(lookahead.next(), Some((_, 'n')))
+                && !lookahead
+                    .peek()
+                    .is_some_and(|(_, c)| Scanner::is_identifier_continue(c));
+            if suffixed {
+                peeker.next();
+                return match RoxBigInt::parse(&string_of_num) {
+                    Some(int) => TokenType::BigInt(Rc::new(int)),
+                    None => {
+                        TokenType::Error(format!("Error parsing big integer {}", string_of_num))
+                    }
+                };
+            }
+        }
+
+        match string_of_num.parse::<f64>() {
             Ok(val) => TokenType::Number(RoxNumber(val)),
             Err(_) => TokenType::Error(format!("Error parsing number {}", string_of_num)),
         }
@@ -72,17 +175,31 @@ impl Scanner {
 
     fn identifier(peeker: &mut Peeker, first_letter: &char) -> TokenType {
         let mut string_accum = first_letter.to_string();
-        while let Some((_, c)) = peeker.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_') {
+        while let Some((_, c)) = peeker.next_if(|(_, c)| Scanner::is_identifier_continue(c)) {
             string_accum.push(c);
         }
 
         Scanner::find_identifier_type(&string_accum)
     }
 
+    fn is_identifier_start(c: &char) -> bool {
+        *c == '_' || is_xid_start(*c)
+    }
+
+    fn is_identifier_continue(c: &char) -> bool {
+        is_xid_continue(*c)
+    }
+
     fn find_identifier_type(id: &str) -> TokenType {
-        let mut id_chars = id.char_indices().peekable();
+        let mut id_chars = id.chars().enumerate().peekable();
         match id_chars.next().unwrap_or((0, '!')) {
-            (.., 'a') => Scanner::check_keyword(&mut id_chars, 2, "nd", id, TokenType::And),
+            (.., 'a') => match id_chars.next().unwrap_or((0, '!')) {
+                (.., 'n') => Scanner::check_keyword(&mut id_chars, 1, "d", id, TokenType::And),
+                (.., 's') => {
+                    Scanner::check_keyword(&mut id_chars, 4, "sert", id, TokenType::Assert)
+                }
+                _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
+            },
             (.., 'b') => Scanner::check_keyword(&mut id_chars, 4, "reak", id, TokenType::Break),
             (.., 'c') => match id_chars.next().unwrap_or((0, '!')) {
                 (.., 'a') => Scanner::check_keyword(&mut id_chars, 2, "se", id, TokenType::Case),
@@ -90,14 +207,22 @@ impl Scanner {
                 (.., 'o') => {
                     Scanner::check_keyword(&mut id_chars, 6, "ntinue", id, TokenType::Continue)
                 }
-                (.., '!') => TokenType::Error(
-                    "Error grabbing next char after 'c' in scanning identifier.".to_string(),
-                ),
                 _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
             },
-            (.., 'd') => Scanner::check_keyword(&mut id_chars, 6, "efault", id, TokenType::Default),
-            (.., 'e') => Scanner::check_keyword(&mut id_chars, 3, "lse", id, TokenType::Else),
+            (.., 'd') => match id_chars.next().unwrap_or((0, '!')) {
+                (.., 'e') => {
+                    Scanner::check_keyword(&mut id_chars, 5, "fault", id, TokenType::Default)
+                }
+                (.., 'o') => Scanner::check_keyword(&mut id_chars, 0, "", id, TokenType::Do),
+                _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
+            },
+            (.., 'e') => match id_chars.next().unwrap_or((0, '!')) {
+                (.., 'l') => Scanner::check_keyword(&mut id_chars, 2, "se", id, TokenType::Else),
+                (.., 'n') => Scanner::check_keyword(&mut id_chars, 2, "um", id, TokenType::Enum),
+                _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
+            },
             (.., 'i') => Scanner::check_keyword(&mut id_chars, 1, "f", id, TokenType::If),
+            (.., 'm') => Scanner::check_keyword(&mut id_chars, 4, "atch", id, TokenType::Match),
             (.., 'n') => Scanner::check_keyword(&mut id_chars, 2, "il", id, TokenType::Nil),
             (.., 'o') => Scanner::check_keyword(&mut id_chars, 1, "r", id, TokenType::Or),
             (.., 'p') => Scanner::check_keyword(&mut id_chars, 4, "rint", id, TokenType::Print),
@@ -107,9 +232,6 @@ impl Scanner {
                 (.., 'w') => {
                     Scanner::check_keyword(&mut id_chars, 4, "itch", id, TokenType::Switch)
                 }
-                (.., '!') => TokenType::Error(
-                    "Error grabbing next char after 's' in scanning identifier.".to_string(),
-                ),
                 _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
             },
             (.., 'v') => Scanner::check_keyword(&mut id_chars, 2, "ar", id, TokenType::Var),
@@ -118,17 +240,11 @@ impl Scanner {
                 (.., 'a') => Scanner::check_keyword(&mut id_chars, 3, "lse", id, TokenType::False),
                 (.., 'o') => Scanner::check_keyword(&mut id_chars, 1, "r", id, TokenType::For),
                 (.., 'u') => Scanner::check_keyword(&mut id_chars, 1, "n", id, TokenType::Fun),
-                (.., '!') => TokenType::Error(
-                    "Error grabbing next char after 'f' in scanning identifier.".to_string(),
-                ),
                 _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
             },
             (.., 't') => match id_chars.next().unwrap_or((0, '!')) {
                 (.., 'h') => Scanner::check_keyword(&mut id_chars, 2, "is", id, TokenType::This),
                 (.., 'r') => Scanner::check_keyword(&mut id_chars, 2, "ue", id, TokenType::True),
-                (.., '!') => TokenType::Error(
-                    "Error grabbing next char after 't' in scanning identifier".to_string(),
-                ),
                 _ => TokenType::Identifier(Rc::new(RoxString::new(id))),
             },
             (.., '!') => {
@@ -162,7 +278,7 @@ impl Scanner {
         let mut num_lines = 1;
 
         for (line_num, line) in source.lines().enumerate() {
-            let mut line_chars: Peeker = line.char_indices().peekable();
+            let mut line_chars: Peeker = line.chars().enumerate().peekable();
             while let Some((char_num, ch)) = line_chars.next() {
                 let token_type = match ch {
                     ':' => TokenType::Colon,
@@ -170,10 +286,20 @@ impl Scanner {
                     ')' => TokenType::RightParen,
                     '{' => TokenType::LeftBrace,
                     '}' => TokenType::RightBrace,
+                    '[' => TokenType::LeftBracket,
+                    ']' => TokenType::RightBracket,
                     ',' => TokenType::Comma,
                     ';' => TokenType::Semicolon,
                     '.' => {
-                        if line_chars.peek().unwrap_or(&(0, ' ')).1.is_numeric() {
+                        if line_chars.peek().unwrap_or(&(0, ' ')).1 == '.' {
+                            line_chars.next();
+                            Scanner::check_next(
+                                &mut line_chars,
+                                '.',
+                                TokenType::DotDot,
+                                TokenType::DotDotDot,
+                            )
+                        } else if line_chars.peek().unwrap_or(&(0, ' ')).1.is_numeric() {
                             while line_chars.next_if(|(_, c)| c.is_numeric()).is_some() {}
                             TokenType::Error(String::from(
                                 "Cannot begin a number in Rox with a dot.",
@@ -182,6 +308,11 @@ impl Scanner {
                             TokenType::Dot
                         }
                     }
+                    '?' => match line_chars.next_if(|(_, c)| *c == '.' || *c == '[') {
+                        Some((_, '.')) => TokenType::QuestionDot,
+                        Some(_) => TokenType::QuestionLeftBracket,
+                        None => TokenType::Error(String::from("Expect '.' or '[' after '?'.")),
+                    },
                     '-' => TokenType::Minus,
                     '+' => TokenType::Plus,
                     '*' => TokenType::Star,
@@ -191,12 +322,18 @@ impl Scanner {
                         TokenType::Bang,
                         TokenType::BangEqual,
                     ),
-                    '=' => Scanner::check_next(
-                        &mut line_chars,
-                        '=',
-                        TokenType::Equal,
-                        TokenType::EqualEqual,
-                    ),
+                    '=' => match line_chars.peek().unwrap_or(&(0, ' ')).1 {
+                        '>' => {
+                            line_chars.next();
+                            TokenType::FatArrow
+                        }
+                        _ => Scanner::check_next(
+                            &mut line_chars,
+                            '=',
+                            TokenType::Equal,
+                            TokenType::EqualEqual,
+                        ),
+                    },
                     '>' => Scanner::check_next(
                         &mut line_chars,
                         '=',
@@ -224,8 +361,13 @@ impl Scanner {
                         }
                     }
                     '"' => Scanner::string(&mut line_chars),
+                    'b' if line_chars.peek().is_some_and(|(_, c)| *c == '"') => {
+                        Scanner::bytes(&mut line_chars)
+                    }
                     '0'..='9' => Scanner::number(&mut line_chars, &ch),
-                    'a'..='z' | 'A'..='Z' => Scanner::identifier(&mut line_chars, &ch),
+                    c if Scanner::is_identifier_start(&c) => {
+                        Scanner::identifier(&mut line_chars, &ch)
+                    }
                     _ => TokenType::Error(String::from("Unexpected char read from source")),
                 };
 
@@ -282,6 +424,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_single_letter_identifiers() {
+        let scanner = Scanner::new();
+        let tokens = scanner.scan_tokens("c s f t");
+
+        assert_eq!(
+            *tokens,
+            vec![
+                Token::new(TokenType::Identifier(Rc::new(RoxString::new("c"))), 1, 1),
+                Token::new(TokenType::Identifier(Rc::new(RoxString::new("s"))), 1, 3),
+                Token::new(TokenType::Identifier(Rc::new(RoxString::new("f"))), 1, 5),
+                Token::new(TokenType::Identifier(Rc::new(RoxString::new("t"))), 1, 7),
+                Token::new(TokenType::EOF, 2, 1),
+            ]
+        );
+    }
+
     #[test]
     fn test_string_literal() {}
 
@@ -289,7 +448,74 @@ mod tests {
     fn test_number_literal() {}
 
     #[test]
-    fn test_identifier() {}
+    fn test_identifier() {
+        let scanner = Scanner::new();
+        let tokens = scanner.scan_tokens("_private größe 名前 x_1");
+
+        assert_eq!(
+            *tokens,
+            vec![
+                Token::new(
+                    TokenType::Identifier(Rc::new(RoxString::new("_private"))),
+                    1,
+                    1
+                ),
+                Token::new(
+                    TokenType::Identifier(Rc::new(RoxString::new("größe"))),
+                    1,
+                    10
+                ),
+                Token::new(
+                    TokenType::Identifier(Rc::new(RoxString::new("名前"))),
+                    1,
+                    16
+                ),
+                Token::new(TokenType::Identifier(Rc::new(RoxString::new("x_1"))), 1, 19),
+                Token::new(TokenType::EOF, 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let scanner = Scanner::new();
+        let tokens = scanner.scan_tokens("\"ünï\" ;");
+
+        assert_eq!(tokens[1], Token::new(TokenType::Semicolon, 1, 7));
+    }
+
+    #[test]
+    fn test_big_int_literal() {
+        let scanner = Scanner::new();
+        let tokens = scanner.scan_tokens("12n 3nope");
+
+        match &tokens[0].token_type {
+            TokenType::BigInt(int) => assert_eq!(int.to_string(), "12"),
+            other => panic!("Expected a big integer, got {:?}", other),
+        }
+        assert!(matches!(tokens[1].token_type, TokenType::Number(_)));
+        assert!(matches!(tokens[2].token_type, TokenType::Identifier(_)));
+    }
+
+    #[test]
+    fn test_bytes_literal_escapes() {
+        let scanner = Scanner::new();
+        let tokens = scanner.scan_tokens(r#"b"a\x00\xFf\\\"" b"\q" b"\x4" b"é""#);
+
+        match &tokens[0].token_type {
+            TokenType::Bytes(bytes) => assert_eq!(bytes.bytes(), b"a\x00\xff\\\""),
+            other => panic!("Expected bytes, got {:?}", other),
+        }
+
+        let errors = [
+            "Invalid escape '\\q' in bytes literal.",
+            "Invalid escape '\\x4' in bytes literal, expected two hex digits.",
+            "Bytes literals can only hold ASCII, use \\x escapes for 'é'.",
+        ];
+        for (token, error) in tokens[1..].iter().zip(errors) {
+            assert_eq!(token.token_type, TokenType::Error(error.to_string()));
+        }
+    }
 
     #[test]
     fn test_keywords() {}
@@ -146,7 +146,7 @@ mod tests {
         let mut s = Stack::new();
 
         for i in 0..31 {
-            s.push(Value::Number(RoxNumber(i as f32)));
+            s.push(Value::Number(RoxNumber(i as f64)));
         }
     }
 
@@ -9,6 +9,7 @@ use crate::STACK_MAX;
 pub struct Stack<T> {
     items: VecDeque<T>,
     size: usize,
+    base: usize,
 }
 
 impl<T> Default for Stack<T>
@@ -28,12 +29,20 @@ where
         Self {
             items: vec![Default::default(); STACK_MAX].into(),
             size: 0,
+            base: 0,
         }
     }
 
     pub fn reset(&mut self) {
         self.items = vec![].into();
         self.size = 0;
+        self.base = 0;
+    }
+
+    /// Moves the slot that local index 0 refers to, returning the
+    /// previous base so that it can be restored.
+    pub fn set_base(&mut self, base: usize) -> usize {
+        std::mem::replace(&mut self.base, base)
     }
 
     pub fn size(&self) -> usize {
@@ -60,11 +69,13 @@ where
     }
 
     pub fn get_and_push_local(&mut self, index: usize) -> Result<(), String> {
+        let index = self.base + index;
         if index >= self.size() {
             return Err("Cannot get local at index beyond stack size".into());
         }
 
-        let local = &self.items[index];
+        // locals are indexed from the bottom of the stack
+        let local = &self.items[self.size - 1 - index];
         self.items.push_front(local.clone());
         self.size += 1;
 
@@ -72,9 +83,15 @@ where
     }
 
     pub fn set_local(&mut self, index: usize) -> Result<(), String> {
+        let index = self.base + index;
+        if index >= self.size() {
+            return Err("Cannot set local at index beyond stack size".into());
+        }
+
+        let slot = self.size - 1 - index;
         match self.peek() {
             Some(local) => {
-                self.items[index] = local.clone();
+                self.items[slot] = local.clone();
                 Ok(())
             }
             None => Err("Error cannot set local in empty stack".into()),
@@ -96,3 +113,28 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Stack;
+    use crate::RoxNumber;
+    use crate::Value;
+
+    #[test]
+    fn test_locals_are_indexed_from_the_bottom() {
+        let mut s = Stack::new();
+        s.push(Value::Number(RoxNumber(1.0)));
+        s.push(Value::Number(RoxNumber(2.0)));
+
+        s.get_and_push_local(0).unwrap();
+        assert_eq!(s.peek().unwrap().to_string(), "1");
+
+        s.set_local(1).unwrap();
+        s.pop();
+        s.get_and_push_local(1).unwrap();
+        assert_eq!(s.peek().unwrap().to_string(), "1");
+
+        assert!(s.get_and_push_local(3).is_err());
+        assert!(s.set_local(3).is_err());
+    }
+}
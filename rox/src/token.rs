@@ -1,7 +1,7 @@
 use std::ops::Deref;
 use std::rc::Rc;
 
-use crate::RoxString;
+use crate::{RoxBigInt, RoxBytes, RoxString};
 
 #[derive(PartialEq, Debug)]
 pub struct TokenStream(Vec<Token>);
@@ -84,7 +84,7 @@ impl Token {
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-pub struct RoxNumber(pub f32);
+pub struct RoxNumber(pub f64);
 
 impl std::cmp::PartialOrd for RoxNumber {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
@@ -139,8 +139,8 @@ impl std::fmt::Display for RoxNumber {
 }
 
 impl Deref for RoxNumber {
-    type Target = f32;
-    fn deref(&self) -> &f32 {
+    type Target = f64;
+    fn deref(&self) -> &f64 {
         &self.0
     }
 }
@@ -152,6 +152,8 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
     Minus,
@@ -172,6 +174,8 @@ pub enum TokenType {
     Identifier(Rc<RoxString>),
     StringLiteral(Rc<RoxString>),
     Number(RoxNumber),
+    BigInt(Rc<RoxBigInt>),
+    Bytes(Rc<RoxBytes>),
     // Keywords.
     And,
     Class,
@@ -189,16 +193,33 @@ pub enum TokenType {
     True,
     Var,
     While,
+    Do,
 
     Break,
     Continue,
 
+    // testing
+    Assert,
+
     // switch
     Colon,
     Default,
     Switch,
     Case,
 
+    // enums and match
+    Enum,
+    Match,
+    FatArrow,
+    DotDot,
+
+    // destructuring
+    DotDotDot,
+
+    // optional chaining
+    QuestionDot,
+    QuestionLeftBracket,
+
     Error(String),
     EOF,
 }
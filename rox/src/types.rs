@@ -1 +1,147 @@
+use crate::{ObjectType, RoxString, Value};
 
+/// The optional type annotations understood by the compiler,
+/// as in `var x: Number = 1;`. Unannotated variables and
+/// expressions whose type cannot be inferred are `Any`, which
+/// is compatible with every other type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoxType {
+    #[default]
+    Any,
+    Number,
+    String,
+    Bool,
+    Nil,
+    List,
+    Map,
+    Enum,
+    Function,
+    BigInt,
+    Set,
+    Tuple,
+    Bytes,
+}
+
+impl RoxType {
+    pub fn from_name(name: &str) -> Option<RoxType> {
+        match name {
+            "Any" => Some(RoxType::Any),
+            "Number" => Some(RoxType::Number),
+            "String" => Some(RoxType::String),
+            "Bool" => Some(RoxType::Bool),
+            "Nil" => Some(RoxType::Nil),
+            "List" => Some(RoxType::List),
+            "Map" => Some(RoxType::Map),
+            "Enum" => Some(RoxType::Enum),
+            "Function" => Some(RoxType::Function),
+            "BigInt" => Some(RoxType::BigInt),
+            "Set" => Some(RoxType::Set),
+            "Tuple" => Some(RoxType::Tuple),
+            "Bytes" => Some(RoxType::Bytes),
+            _ => None,
+        }
+    }
+
+    pub fn of_value(value: &Value) -> RoxType {
+        match value {
+            Value::Number(_) => RoxType::Number,
+            Value::Boolean(_) => RoxType::Bool,
+            Value::Nil => RoxType::Nil,
+            Value::Object(obj) => match obj.object_type {
+                ObjectType::ObjString(_) => RoxType::String,
+                ObjectType::ObjEnumVariant(_) => RoxType::Enum,
+                ObjectType::ObjList(_) => RoxType::List,
+                ObjectType::ObjDict(_) => RoxType::Map,
+                ObjectType::ObjNative(_) => RoxType::Function,
+                ObjectType::ObjBigInt(_) => RoxType::BigInt,
+                ObjectType::ObjSet(_) => RoxType::Set,
+                ObjectType::ObjTuple(_) => RoxType::Tuple,
+                ObjectType::ObjBytes(_) => RoxType::Bytes,
+            },
+            Value::Error => RoxType::Any,
+        }
+    }
+
+    /// Checks whether a value of type other may be stored
+    /// somewhere annotated with this type.
+    pub fn accepts(&self, other: &RoxType) -> bool {
+        *self == RoxType::Any || *other == RoxType::Any || self == other
+    }
+}
+
+impl std::fmt::Display for RoxType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoxType::Any => write!(f, "Any"),
+            RoxType::Number => write!(f, "Number"),
+            RoxType::String => write!(f, "String"),
+            RoxType::Bool => write!(f, "Bool"),
+            RoxType::Nil => write!(f, "Nil"),
+            RoxType::List => write!(f, "List"),
+            RoxType::Map => write!(f, "Map"),
+            RoxType::Enum => write!(f, "Enum"),
+            RoxType::Function => write!(f, "Function"),
+            RoxType::BigInt => write!(f, "BigInt"),
+            RoxType::Set => write!(f, "Set"),
+            RoxType::Tuple => write!(f, "Tuple"),
+            RoxType::Bytes => write!(f, "Bytes"),
+        }
+    }
+}
+
+/// The annotations of global variables seen so far. Like
+/// globals, they outlive a single compilation, so the VM keeps
+/// them for `eval` and later calls to `interpret`. Local
+/// variable annotations are kept alongside their slots in
+/// `frontend::Locals`.
+#[derive(Debug, Default, Clone)]
+pub struct GlobalTypes {
+    annotations: Vec<(RoxString, RoxType)>,
+}
+
+impl GlobalTypes {
+    pub fn new() -> GlobalTypes {
+        GlobalTypes {
+            annotations: vec![],
+        }
+    }
+
+    pub fn annotate(&mut self, name: &RoxString, rox_type: RoxType) {
+        self.annotations.retain(|(existing, _)| existing != name);
+        self.annotations.push((name.clone(), rox_type));
+    }
+
+    pub fn resolve(&self, name: &RoxString) -> RoxType {
+        self.annotations
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map_or(RoxType::Any, |(_, rox_type)| *rox_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RoxNumber, RoxObject};
+
+    #[test]
+    fn test_any_accepts_everything() {
+        assert!(RoxType::Any.accepts(&RoxType::Number));
+        assert!(RoxType::String.accepts(&RoxType::Any));
+        assert!(!RoxType::String.accepts(&RoxType::Number));
+    }
+
+    #[test]
+    fn test_type_of_values() {
+        assert_eq!(
+            RoxType::of_value(&Value::Number(RoxNumber(1.0))),
+            RoxType::Number
+        );
+        assert_eq!(
+            RoxType::of_value(&Value::Object(RoxObject::new(ObjectType::ObjString(
+                RoxString::new("a")
+            )))),
+            RoxType::String
+        );
+    }
+}
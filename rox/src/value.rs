@@ -1,5 +1,59 @@
-use crate::{ObjectType, RoxNumber, RoxObject};
-use std::{fmt::Write, ops};
+use crate::{ObjectType, RoxBigInt, RoxDict, RoxNumber, RoxObject};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    fmt::Write,
+    hash::{Hash, Hasher},
+    ops,
+    thread::LocalKey,
+};
+
+thread_local! {
+    // the lists and maps being printed, and the pairs of them being
+    // compared, so that a value containing itself is not followed
+    // around its cycle forever
+    static PRINTING: RefCell<Vec<usize>> = const { RefCell::new(vec![]) };
+    static COMPARING: RefCell<Vec<(usize, usize)>> = const { RefCell::new(vec![]) };
+    static HASHING: RefCell<Vec<usize>> = const { RefCell::new(vec![]) };
+}
+
+/// Runs visit with key on the path, or returns None when key is
+/// already on it because a container was reached through itself.
+fn visit_once<K: PartialEq, T>(
+    path: &'static LocalKey<RefCell<Vec<K>>>,
+    key: K,
+    visit: impl FnOnce() -> T,
+) -> Option<T> {
+    if path.with(|path| path.borrow().contains(&key)) {
+        return None;
+    }
+
+    path.with(|path| path.borrow_mut().push(key));
+    let result = visit();
+    path.with(|path| path.borrow_mut().pop());
+    Some(result)
+}
+
+/// Displays the list or map at address, or writes the placeholder
+/// when it is already being displayed further up, as in `[[...]]`.
+pub(crate) fn display_once(
+    f: &mut std::fmt::Formatter<'_>,
+    address: usize,
+    placeholder: &str,
+    display: impl FnOnce(&mut std::fmt::Formatter<'_>) -> std::fmt::Result,
+) -> std::fmt::Result {
+    visit_once(&PRINTING, address, || display(f)).unwrap_or_else(|| write!(f, "{}", placeholder))
+}
+
+/// Compares the lists or maps at addresses a and b, giving same when
+/// they are one container or when this pair is already being compared
+/// further up, so that cyclic values compare as equal.
+pub(crate) fn compare_once<T>(a: usize, b: usize, same: T, compare: impl FnOnce() -> T) -> T {
+    if a == b {
+        return same;
+    }
+    visit_once(&COMPARING, (a, b), compare).unwrap_or(same)
+}
 
 #[derive(Debug, Default, Clone)]
 pub struct Values {
@@ -39,11 +93,140 @@ impl Values {
     }
 }
 
-impl PartialOrd for Value {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+impl Value {
+    pub fn as_big_int(&self) -> Option<&RoxBigInt> {
+        match self {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBigInt(int),
+            }) => Some(int),
+            _ => None,
+        }
+    }
+
+    /// Checks for numbers and big integers, which can be
+    /// compared and combined with each other.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Value::Number(_)) || self.as_big_int().is_some()
+    }
+
+    /// A total ordering over all values, used for sorting. Values of
+    /// different types are ordered by type, in this order: nil,
+    /// booleans, numbers and big integers, strings, enum variants,
+    /// lists, maps, functions, sets, tuples, bytes. Values of the same
+    /// type are ordered by their contents, and numbers that compare
+    /// equal with `==` are also equal here.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => a
+                .partial_cmp(b)
+                .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan())),
+            // only NaN is unordered against a big integer, and sorts after it
+            _ if self.is_numeric() && other.is_numeric() => {
+                self.partial_cmp(other).unwrap_or_else(|| {
+                    matches!(self, Value::Number(_)).cmp(&matches!(other, Value::Number(_)))
+                })
+            }
+            (Value::Object(a), Value::Object(b)) => match (&a.object_type, &b.object_type) {
+                (ObjectType::ObjString(a), ObjectType::ObjString(b)) => a.cmp(b),
+                (ObjectType::ObjEnumVariant(a), ObjectType::ObjEnumVariant(b)) => a
+                    .enum_name
+                    .cmp(&b.enum_name)
+                    .then_with(|| a.variant.cmp(&b.variant))
+                    .then_with(|| Value::total_cmp_all(&a.payload, &b.payload)),
+                (ObjectType::ObjList(a), ObjectType::ObjList(b)) => {
+                    compare_once(a.address(), b.address(), Ordering::Equal, || {
+                        Value::total_cmp_all(&a.values(), &b.values())
+                    })
+                }
+                (ObjectType::ObjDict(a), ObjectType::ObjDict(b)) => {
+                    let entries = |dict: &RoxDict| {
+                        dict.sorted_keys()
+                            .into_iter()
+                            .flat_map(|key| {
+                                let value = dict.get(&key).unwrap_or_default();
+                                [
+                                    Value::Object(RoxObject::new(ObjectType::ObjString(key))),
+                                    value,
+                                ]
+                            })
+                            .collect::<Vec<_>>()
+                    };
+                    compare_once(a.address(), b.address(), Ordering::Equal, || {
+                        Value::total_cmp_all(&entries(a), &entries(b))
+                    })
+                }
+                (ObjectType::ObjNative(a), ObjectType::ObjNative(b)) => a.name.cmp(b.name),
+                (ObjectType::ObjSet(a), ObjectType::ObjSet(b)) => {
+                    Value::total_cmp_all(&a.sorted_values(), &b.sorted_values())
+                }
+                (ObjectType::ObjTuple(a), ObjectType::ObjTuple(b)) => {
+                    Value::total_cmp_all(a.values(), b.values())
+                }
+                (ObjectType::ObjBytes(a), ObjectType::ObjBytes(b)) => a.cmp(b),
+                _ => self.type_rank().cmp(&other.type_rank()),
+            },
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+
+    /// Checks that a value and everything in it can never change, as
+    /// set elements must be. Frozen lists, maps and sets qualify, since
+    /// freezing also freezes their contents.
+    pub fn is_immutable(&self) -> bool {
+        match self {
+            Value::Object(obj) => match &obj.object_type {
+                ObjectType::ObjList(list) => list.is_frozen(),
+                ObjectType::ObjDict(dict) => dict.is_frozen(),
+                ObjectType::ObjSet(set) => set.is_frozen(),
+                ObjectType::ObjTuple(tuple) => tuple.values().iter().all(Value::is_immutable),
+                ObjectType::ObjEnumVariant(variant) => {
+                    variant.payload.iter().all(Value::is_immutable)
+                }
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn total_cmp_all(a: &[Value], b: &[Value]) -> Ordering {
+        a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| a.total_cmp(b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len()))
+    }
+
+    fn type_rank(&self) -> u8 {
         match self {
-            Value::Number(self_num) => match other {
-                Value::Number(other_num) => self_num.partial_cmp(other_num),
+            Value::Nil => 0,
+            Value::Boolean(_) => 1,
+            Value::Number(_) => 2,
+            Value::Object(obj) => match obj.object_type {
+                ObjectType::ObjString(_) => 3,
+                ObjectType::ObjEnumVariant(_) => 4,
+                ObjectType::ObjList(_) => 5,
+                ObjectType::ObjDict(_) => 6,
+                ObjectType::ObjNative(_) => 7,
+                ObjectType::ObjBigInt(_) => 2,
+                ObjectType::ObjSet(_) => 8,
+                ObjectType::ObjTuple(_) => 9,
+                ObjectType::ObjBytes(_) => 10,
+            },
+            Value::Error => 11,
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+            (Value::Number(a), _) => other.as_big_int()?.cmp_f64(**a).map(Ordering::reverse),
+            (_, Value::Number(b)) => self.as_big_int()?.cmp_f64(**b),
+            (Value::Object(a), Value::Object(b)) => match (&a.object_type, &b.object_type) {
+                (ObjectType::ObjString(a), ObjectType::ObjString(b)) => a.partial_cmp(b),
+                (ObjectType::ObjBigInt(a), ObjectType::ObjBigInt(b)) => a.partial_cmp(b),
                 _ => None,
             },
             _ => None,
@@ -56,32 +239,73 @@ impl PartialEq for Value {
         match self {
             Value::Number(a_num) => match other {
                 Value::Number(b_num) => a_num == b_num,
-                _ => false,
+                _ => self.partial_cmp(other) == Some(Ordering::Equal),
             },
             Value::Boolean(a_bool) => match other {
                 Value::Boolean(b_bool) => a_bool == b_bool,
                 _ => false,
             },
             Value::Nil => matches!(other, Value::Nil),
-            Value::Object(obj) => match &obj.object_type {
-                ObjectType::ObjString(string_one) => match other {
-                    Value::Object(obj_two) => match &obj_two.object_type {
-                        ObjectType::ObjString(string_two) => string_one == string_two,
-                    },
-                    _ => false,
-                },
+            Value::Object(obj) => match other {
+                Value::Object(obj_two) => obj.object_type == obj_two.object_type,
+                Value::Number(_) => self.partial_cmp(other) == Some(Ordering::Equal),
+                _ => false,
             },
             _ => false,
         }
     }
 }
 
+/// Values that are equal with `==` hash alike, so whole numbers
+/// hash like the big integers they are equal to, and lists, maps
+/// and sets hash their contents. A list or map reached again
+/// through itself adds nothing more to the hash.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Value::Nil | Value::Error => {}
+            Value::Boolean(b) => b.hash(state),
+            Value::Number(num) => match RoxBigInt::from_f64(**num) {
+                Some(int) => int.hash(state),
+                None => num.to_bits().hash(state),
+            },
+            Value::Object(obj) => match &obj.object_type {
+                ObjectType::ObjString(string) => string.hash(state),
+                ObjectType::ObjEnumVariant(variant) => {
+                    variant.enum_name.hash(state);
+                    variant.variant.hash(state);
+                    variant.payload.hash(state);
+                }
+                ObjectType::ObjList(list) => {
+                    visit_once(&HASHING, list.address(), || list.values().hash(state));
+                }
+                ObjectType::ObjDict(dict) => {
+                    visit_once(&HASHING, dict.address(), || {
+                        for key in dict.sorted_keys() {
+                            dict.get(&key).hash(state);
+                            key.hash(state);
+                        }
+                    });
+                }
+                ObjectType::ObjNative(native) => native.name.hash(state),
+                ObjectType::ObjBigInt(int) => int.hash(state),
+                ObjectType::ObjSet(set) => set.sorted_values().hash(state),
+                ObjectType::ObjTuple(tuple) => tuple.hash(state),
+                ObjectType::ObjBytes(bytes) => bytes.hash(state),
+            },
+        }
+    }
+}
+
 impl ops::Neg for Value {
     type Output = Value;
 
     fn neg(self) -> Self::Output {
         match self {
             Value::Number(num) => Value::Number(-num),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBigInt(int),
+            }) => Value::Object(RoxObject::new(ObjectType::ObjBigInt(-&int))),
             _ => Value::Error,
         }
     }
@@ -163,6 +387,14 @@ impl std::fmt::Display for Value {
             Value::Nil => write!(f, "nil"),
             Value::Object(obj) => match &obj.object_type {
                 ObjectType::ObjString(s) => write!(f, "\"{}\"", s),
+                ObjectType::ObjEnumVariant(variant) => write!(f, "{}", variant),
+                ObjectType::ObjList(list) => write!(f, "{}", list),
+                ObjectType::ObjDict(dict) => write!(f, "{}", dict),
+                ObjectType::ObjNative(native) => write!(f, "{}", native),
+                ObjectType::ObjBigInt(int) => write!(f, "{}", int),
+                ObjectType::ObjSet(set) => write!(f, "{}", set),
+                ObjectType::ObjTuple(tuple) => write!(f, "{}", tuple),
+                ObjectType::ObjBytes(bytes) => write!(f, "{}", bytes),
                 //_ => unimplemented!("Unimplemented object type display!"),
             },
             Value::Error => write!(f, "Value<Error>"),
@@ -1,39 +1,180 @@
-use std::io::{stdout, Write};
+use std::io::{stdout, BufRead, Write};
 
 use termion::raw::IntoRawMode;
 
+use crate::frontend::Enums;
+use crate::natives::{constants, natives};
+use crate::types::GlobalTypes;
+use crate::NativeContext;
 use crate::ObjectType;
 use crate::OpCode;
+use crate::RoxBigInt;
+use crate::RoxBytes;
+use crate::RoxDict;
+use crate::RoxEnumVariant;
+use crate::RoxList;
 use crate::RoxMap;
 use crate::RoxObject;
 use crate::RoxString;
+use crate::RoxTuple;
+use crate::RoxType;
 use crate::Stack;
 use crate::Table;
 use crate::Value;
 use crate::DEBUG_MODE;
-use crate::{Chunk, Compiler};
+use crate::{Chunk, Compiled, Compiler};
 use crate::{InterpretError, InterpretOk, InterpretResult};
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// The largest whole number above which not every whole number
+/// can be represented exactly.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// The outcome of one `test "name" { ... }` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub name: String,
+    pub failure: Option<String>,
+}
+
+/// A test block that is currently running, and where
+/// to resume if it fails.
+#[derive(Debug)]
+struct RunningTest {
+    name: String,
+    stack_size: usize,
+    bindings_size: usize,
+    end_ip: usize,
+}
 
 #[derive(Debug)]
 pub struct VM {
     ip: usize,
     stack: Stack<Value>,
     globals: Table<RoxString, Value>,
+    bindings: Vec<Value>,
+    // the binding that match binding index 0 refers to
+    bindings_base: usize,
+    enums: Enums,
+    global_types: GlobalTypes,
+    type_guards: bool,
+    integer_promotion: bool,
+    natives: NativeContext,
+    script_args: Vec<String>,
+    test_mode: bool,
+    running_test: Option<RunningTest>,
+    test_results: Vec<TestResult>,
 }
 
 impl VM {
     pub fn new() -> VM {
-        VM {
+        let mut vm = VM {
             ip: 0,
             stack: Stack::new(),
             globals: Table::new(),
-        }
+            bindings: vec![],
+            bindings_base: 0,
+            enums: Enums::new(),
+            global_types: GlobalTypes::new(),
+            type_guards: false,
+            integer_promotion: false,
+            natives: NativeContext::new(),
+            script_args: vec![],
+            test_mode: false,
+            running_test: None,
+            test_results: vec![],
+        };
+        vm.define_natives();
+        vm
     }
 
     pub fn reset(&mut self) {
         self.ip = 0;
         self.stack.reset();
         self.globals.reset();
+        self.bindings.clear();
+        self.bindings_base = 0;
+        self.enums = Enums::new();
+        self.global_types = GlobalTypes::new();
+        self.natives.clear_exit_code();
+        self.running_test = None;
+        self.test_results.clear();
+        self.define_natives();
+    }
+
+    /// Runs `test` blocks instead of skipping them. A failing test
+    /// is recorded and the script carries on after its block.
+    pub fn set_test_mode(&mut self, enabled: bool) {
+        self.test_mode = enabled;
+    }
+
+    pub fn test_results(&self) -> &[TestResult] {
+        &self.test_results
+    }
+
+    /// Seeds the generator behind `random` and the other random
+    /// natives, so that a script replays the same numbers.
+    pub fn seed_random(&mut self, seed: u64) {
+        self.natives.seed_random(seed);
+    }
+
+    /// Confines the file natives to the directory root, so that
+    /// scripts cannot read or write files outside of it.
+    pub fn set_sandbox_root(&mut self, root: &Path) -> std::io::Result<()> {
+        self.natives.set_sandbox_root(root)
+    }
+
+    /// Reads script input from reader instead of stdin, for
+    /// example from an in-memory buffer in tests.
+    pub fn set_input(&mut self, reader: impl BufRead + 'static) {
+        self.natives.set_input(Box::new(reader));
+    }
+
+    /// Sets the command line arguments given to the script,
+    /// which it reads from the global `args` list.
+    pub fn set_script_args(&mut self, args: Vec<String>) {
+        self.script_args = args;
+        self.define_natives();
+    }
+
+    /// The status the script asked to exit with by calling `exit`.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.natives.exit_code()
+    }
+
+    /// Defines the native functions and constants as globals.
+    fn define_natives(&mut self) {
+        for native in natives() {
+            let name = RoxString::new(native.name);
+            let native = Value::Object(RoxObject::new(ObjectType::ObjNative(native)));
+            self.globals.set(&name, &native);
+        }
+
+        for (name, value) in constants() {
+            self.globals.set(&RoxString::new(name), &value);
+        }
+
+        let args = self
+            .script_args
+            .iter()
+            .map(|arg| Value::Object(RoxObject::new(ObjectType::ObjString(RoxString::new(arg)))))
+            .collect();
+        let args = Value::Object(RoxObject::new(ObjectType::ObjList(RoxList::new(args))));
+        self.globals.set(&RoxString::new("args"), &args);
+    }
+
+    /// Enables runtime checks of type annotations, so that a
+    /// mistyped value stored in an annotated variable is a
+    /// runtime error even when the compiler could not infer it.
+    pub fn set_type_guards(&mut self, enabled: bool) {
+        self.type_guards = enabled;
+    }
+
+    /// Enables promotion of whole number arithmetic to big integers
+    /// when a result is too large to be represented exactly.
+    pub fn set_integer_promotion(&mut self, enabled: bool) {
+        self.integer_promotion = enabled;
     }
 
     fn read_byte(code: &[OpCode], ip: usize) -> Option<OpCode> {
@@ -64,6 +205,16 @@ impl VM {
         }
     }
 
+    fn read_variant(values: &[Value], tag_index: usize) -> RoxEnumVariant {
+        match VM::read_constant(values, tag_index) {
+            Some(Value::Object(obj)) => match obj.object_type {
+                ObjectType::ObjEnumVariant(variant) => variant,
+                _ => panic!("Error variant tag was not located at index {}", tag_index),
+            },
+            _ => panic!("Error Value object was not located at index {}", tag_index),
+        }
+    }
+
     fn incr_ip(&mut self) -> usize {
         let current_ip = self.ip;
         self.ip += 1;
@@ -73,6 +224,40 @@ impl VM {
 
     fn run(&mut self, chunk: &Chunk) -> InterpretResult {
         loop {
+            match self.execute(chunk) {
+                Err(error) if self.running_test.is_some() => self.fail_test(error),
+                result => return result,
+            }
+        }
+    }
+
+    /// Records the failure of the running test and unwinds
+    /// the VM to just past the test's block.
+    fn fail_test(&mut self, error: InterpretError) {
+        let test = self
+            .running_test
+            .take()
+            .expect("Error failing test with no test running");
+
+        while self.stack.size() > test.stack_size {
+            self.stack.pop();
+        }
+        self.bindings.truncate(test.bindings_size);
+        self.ip = test.end_ip;
+
+        self.test_results.push(TestResult {
+            name: test.name,
+            failure: Some(error.to_string()),
+        });
+    }
+
+    fn execute(&mut self, chunk: &Chunk) -> InterpretResult {
+        loop {
+            // a call to the exit native stops the script
+            if self.natives.exit_code().is_some() {
+                return Ok(InterpretOk);
+            }
+
             let current_ip = self.incr_ip();
 
             // read next instruction
@@ -179,6 +364,7 @@ impl VM {
                     // check for non number types
                     let val = match val {
                         Value::Number(num) => Value::Number(num),
+                        val if val.as_big_int().is_some() => val,
                         _ => {
                             return Err(InterpretError::RuntimeError(
                                 "Cannot negate non-number type.".to_string(),
@@ -196,27 +382,15 @@ impl VM {
                         self.concatenate(str_1, str_2);
                     } else {
                         // otherwise only numbers are addable
-                        let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
-                        self.stack.push(a + b); // push result
+                        let result = self.arithmetic(chunk, instruction, a, b)?;
+                        self.stack.push(result); // push result
                     }
                 }
-                OpCode::OpSubtract => {
-                    let b = self.stack.pop().unwrap(); // rhs operand
-                    let a = self.stack.pop().unwrap(); // lhs operand
-                    let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
-                    self.stack.push(a - b); // push result
-                }
-                OpCode::OpMultiply => {
+                OpCode::OpSubtract | OpCode::OpMultiply | OpCode::OpDivide => {
                     let b = self.stack.pop().unwrap(); // rhs operand
                     let a = self.stack.pop().unwrap(); // lhs operand
-                    let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
-                    self.stack.push(a * b); // push result
-                }
-                OpCode::OpDivide => {
-                    let b = self.stack.pop().unwrap(); // rhs operand
-                    let a = self.stack.pop().unwrap(); // lhs operand
-                    let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
-                    self.stack.push(a / b); // push result
+                    let result = self.arithmetic(chunk, instruction, a, b)?;
+                    self.stack.push(result); // push result
                 }
                 OpCode::OpEqual => {
                     let b = self.stack.pop().unwrap(); // rhs
@@ -226,14 +400,14 @@ impl VM {
                 OpCode::OpGreater => {
                     let b = self.stack.pop().unwrap(); // rhs operand
                     let a = self.stack.pop().unwrap(); // lhs operand
-                    let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
-                    self.stack.push(Value::Boolean(a > b)); // push result
+                    let result = self.relate(chunk, &a, &b, Ordering::Greater)?;
+                    self.stack.push(result); // push result
                 }
                 OpCode::OpLess => {
                     let b = self.stack.pop().unwrap(); // rhs operand
                     let a = self.stack.pop().unwrap(); // lhs operand
-                    let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
-                    self.stack.push(Value::Boolean(a < b)); // push result
+                    let result = self.relate(chunk, &a, &b, Ordering::Less)?;
+                    self.stack.push(result); // push result
                 }
                 OpCode::OpPrint => {
                     writeln!(
@@ -264,10 +438,556 @@ impl VM {
                 OpCode::OpLoop(jump) => {
                     self.ip -= jump;
                 }
+                OpCode::OpDup => {
+                    let top = self
+                        .stack
+                        .peek()
+                        .expect("Error peeking stack in Dup")
+                        .clone();
+                    self.stack.push(top);
+                }
+                OpCode::OpVariant(tag_index, arity) => {
+                    let tag = VM::read_variant(&chunk.constants.values, tag_index);
+
+                    let mut payload = vec![Value::Nil; arity];
+                    for slot in payload.iter_mut().rev() {
+                        *slot = self.stack.pop().expect("Error popping variant payload");
+                    }
+
+                    let variant =
+                        RoxObject::new(ObjectType::ObjEnumVariant(tag.with_payload(payload)));
+                    self.stack.push(Value::Object(variant));
+                }
+                OpCode::OpMatchVariant(tag_index) => {
+                    let tag = VM::read_variant(&chunk.constants.values, tag_index);
+
+                    let is_match = match self.stack.peek() {
+                        Some(Value::Object(obj)) => match &obj.object_type {
+                            ObjectType::ObjEnumVariant(variant) => variant.same_tag(&tag),
+                            _ => false,
+                        },
+                        _ => false,
+                    };
+                    self.stack.push(Value::Boolean(is_match));
+                }
+                OpCode::OpMatchRange(low_index, high_index) => {
+                    let low = VM::read_constant(&chunk.constants.values, low_index);
+                    let high = VM::read_constant(&chunk.constants.values, high_index);
+
+                    // low bound is inclusive and high bound is exclusive
+                    let is_match = match (self.stack.peek(), low, high) {
+                        (Some(value @ Value::Number(_)), Some(low), Some(high)) => {
+                            *value >= low && *value < high
+                        }
+                        _ => false,
+                    };
+                    self.stack.push(Value::Boolean(is_match));
+                }
+                OpCode::OpBindPayload(arity) => {
+                    let payload = match self.stack.peek() {
+                        Some(Value::Object(obj)) => match &obj.object_type {
+                            ObjectType::ObjEnumVariant(variant) => variant.payload.clone(),
+                            _ => vec![],
+                        },
+                        _ => vec![],
+                    };
+
+                    if payload.len() != arity {
+                        let line = chunk.get_line(self.ip - 1);
+                        return Err(InterpretError::RuntimeError(format!(
+                            "[line {}]: Cannot bind {} payload values from a variant with {}.",
+                            line,
+                            arity,
+                            payload.len()
+                        )));
+                    }
+                    self.bindings.extend(payload);
+                }
+                OpCode::OpUnbind(count) => {
+                    self.bindings.truncate(self.bindings.len() - count);
+                }
+                OpCode::OpGetBinding(index) => {
+                    let binding = self
+                        .bindings
+                        .get(self.bindings_base + index)
+                        .unwrap_or_else(|| panic!("No match binding at index {}", index))
+                        .clone();
+                    self.stack.push(binding);
+                }
+                OpCode::OpBuildList(count) => {
+                    let mut values = vec![Value::Nil; count];
+                    for slot in values.iter_mut().rev() {
+                        *slot = self.stack.pop().expect("Error popping list element");
+                    }
+
+                    let list = RoxObject::new(ObjectType::ObjList(RoxList::new(values)));
+                    self.stack.push(Value::Object(list));
+                }
+                OpCode::OpBuildTuple(count) => {
+                    let mut values = vec![Value::Nil; count];
+                    for slot in values.iter_mut().rev() {
+                        *slot = self.stack.pop().expect("Error popping tuple element");
+                    }
+
+                    let tuple = RoxObject::new(ObjectType::ObjTuple(RoxTuple::new(values)));
+                    self.stack.push(Value::Object(tuple));
+                }
+                OpCode::OpBuildMap(count) => {
+                    let mut entries = vec![];
+                    for _ in 0..count {
+                        let value = self.stack.pop().expect("Error popping map value");
+                        let key = self.stack.pop().expect("Error popping map key");
+                        entries.push((key, value));
+                    }
+
+                    let dict = RoxDict::new();
+                    for (key, value) in entries.iter().rev() {
+                        match key {
+                            Value::Object(RoxObject {
+                                object_type: ObjectType::ObjString(key),
+                            }) => dict.set(key, value),
+                            _ => return Err(self.runtime_error(chunk, "Map keys must be strings.")),
+                        }
+                    }
+                    self.stack
+                        .push(Value::Object(RoxObject::new(ObjectType::ObjDict(dict))));
+                }
+                OpCode::OpGetIndex => {
+                    let index = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    let value = self.get_index(chunk, &target, &index)?;
+                    self.stack.push(value);
+                }
+                OpCode::OpSetIndex => {
+                    let value = self.stack.pop().unwrap();
+                    let index = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    self.set_index(chunk, &target, &index, &value)?;
+                    self.stack.push(value);
+                }
+                OpCode::OpCheckLength(count, has_rest) => {
+                    let length = match self.stack.peek() {
+                        Some(Value::Object(RoxObject {
+                            object_type: ObjectType::ObjList(list),
+                        })) => list.length(),
+                        Some(Value::Object(RoxObject {
+                            object_type: ObjectType::ObjTuple(tuple),
+                        })) => tuple.length(),
+                        Some(value) => {
+                            let message = format!("Cannot destructure non-list value {}.", value);
+                            return Err(self.runtime_error(chunk, &message));
+                        }
+                        None => panic!("Error peeking stack in CheckLength"),
+                    };
+
+                    if length < count || (!has_rest && length > count) {
+                        let message = format!(
+                            "Cannot destructure list of length {} into {} variables.",
+                            length, count
+                        );
+                        return Err(self.runtime_error(chunk, &message));
+                    }
+                }
+                OpCode::OpListRest(start) => match self.stack.pop() {
+                    Some(Value::Object(RoxObject {
+                        object_type: ObjectType::ObjList(list),
+                    })) => {
+                        let rest = RoxObject::new(ObjectType::ObjList(list.slice_from(start)));
+                        self.stack.push(Value::Object(rest));
+                    }
+                    Some(Value::Object(RoxObject {
+                        object_type: ObjectType::ObjTuple(tuple),
+                    })) => {
+                        let rest = tuple.values().get(start..).unwrap_or_default().to_vec();
+                        let rest = RoxObject::new(ObjectType::ObjTuple(RoxTuple::new(rest)));
+                        self.stack.push(Value::Object(rest));
+                    }
+                    _ => return Err(self.runtime_error(chunk, "Can only take the rest of a list.")),
+                },
+                OpCode::OpCheckType(expected) => {
+                    let actual = RoxType::of_value(
+                        self.stack.peek().expect("Error peeking stack in CheckType"),
+                    );
+
+                    if !expected.accepts(&actual) {
+                        let message =
+                            format!("Type error: expected {} but got {}.", expected, actual);
+                        return Err(self.runtime_error(chunk, &message));
+                    }
+                }
+                OpCode::OpGetProperty(name_index) => {
+                    let name = VM::read_string(&chunk.constants.values, name_index);
+                    let receiver = self.stack.pop().unwrap();
+
+                    let value = self.get_property(chunk, &receiver, &name)?;
+                    self.stack.push(value);
+                }
+                OpCode::OpSetProperty(name_index) => {
+                    let name = VM::read_string(&chunk.constants.values, name_index);
+                    let value = self.stack.pop().unwrap();
+                    let receiver = self.stack.pop().unwrap();
+
+                    match receiver {
+                        Value::Object(RoxObject {
+                            object_type: ObjectType::ObjDict(dict),
+                        }) => {
+                            dict.check_mutable()
+                                .map_err(|message| self.runtime_error(chunk, &message))?;
+                            dict.set(&name, &value);
+                        }
+                        _ => {
+                            let message =
+                                format!("Cannot set property '{}' on {}.", name, receiver);
+                            return Err(self.runtime_error(chunk, &message));
+                        }
+                    }
+                    self.stack.push(value);
+                }
+                OpCode::OpCall(arg_count) => {
+                    let mut args = vec![Value::Nil; arg_count];
+                    for slot in args.iter_mut().rev() {
+                        *slot = self.stack.pop().expect("Error popping call argument");
+                    }
+                    let callee = self.stack.pop().expect("Error popping callee");
+
+                    let result = self.call_value(chunk, &callee, &args)?;
+                    self.stack.push(result);
+                }
+                OpCode::OpInvoke(name_index, arg_count) => {
+                    let name = VM::read_string(&chunk.constants.values, name_index);
+                    let mut args = vec![Value::Nil; arg_count];
+                    for slot in args.iter_mut().rev() {
+                        *slot = self.stack.pop().expect("Error popping method argument");
+                    }
+                    let receiver = self.stack.pop().expect("Error popping method receiver");
+
+                    let result = match &receiver {
+                        Value::Object(RoxObject {
+                            object_type: ObjectType::ObjString(string),
+                        }) => string
+                            .invoke(&name, &args)
+                            .map_err(|message| self.runtime_error(chunk, &message))?,
+                        Value::Object(RoxObject {
+                            object_type: ObjectType::ObjSet(set),
+                        }) => set
+                            .invoke(&name, &args)
+                            .map_err(|message| self.runtime_error(chunk, &message))?,
+                        Value::Object(RoxObject {
+                            object_type: ObjectType::ObjBytes(bytes),
+                        }) => bytes
+                            .invoke(&name, &args)
+                            .map_err(|message| self.runtime_error(chunk, &message))?,
+                        // functions stored in a map are called like methods
+                        _ => {
+                            let callee = self.get_property(chunk, &receiver, &name)?;
+                            self.call_value(chunk, &callee, &args)?
+                        }
+                    };
+                    self.stack.push(result);
+                }
+                OpCode::OpGetSlice => {
+                    let end = self.stack.pop().unwrap();
+                    let start = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    let value = self.get_slice(chunk, &target, &start, &end)?;
+                    self.stack.push(value);
+                }
+                OpCode::OpAssert => {
+                    let message = self.stack.pop().unwrap();
+                    let condition = self.stack.pop().unwrap();
+
+                    if self.is_falsey(condition) {
+                        let message = match message {
+                            Value::Nil => String::from("Assertion failed."),
+                            Value::Object(RoxObject {
+                                object_type: ObjectType::ObjString(message),
+                            }) => format!("Assertion failed: {}", message),
+                            message => format!("Assertion failed: {}", message),
+                        };
+                        return Err(self.runtime_error(chunk, &message));
+                    }
+                }
+                OpCode::OpTest(name_index, jump) => {
+                    let jump_offset =
+                        jump.unwrap_or_else(|| panic!("Unknown jump offset for Test"));
+
+                    if self.test_mode {
+                        self.running_test = Some(RunningTest {
+                            name: VM::read_string(&chunk.constants.values, name_index).to_string(),
+                            stack_size: self.stack.size(),
+                            bindings_size: self.bindings.len(),
+                            end_ip: self.ip + jump_offset,
+                        });
+                    } else {
+                        self.ip += jump_offset;
+                    }
+                }
+                OpCode::OpEndTest => {
+                    if let Some(test) = self.running_test.take() {
+                        self.test_results.push(TestResult {
+                            name: test.name,
+                            failure: None,
+                        });
+                    }
+                }
+                OpCode::OpJumpIfNil(jump) => {
+                    let jump_offset =
+                        jump.unwrap_or_else(|| panic!("Unknown jump offset for JumpIfNil"));
+                    if let Some(Value::Nil) = self.stack.peek() {
+                        self.ip += jump_offset;
+                    }
+                }
+                OpCode::OpMatchError => {
+                    let value = self.stack.pop().unwrap();
+                    let line = chunk.get_line(self.ip - 1);
+                    return Err(InterpretError::RuntimeError(format!(
+                        "[line {}]: No match arm for value {}.",
+                        line, value
+                    )));
+                }
+            }
+        }
+    }
+
+    fn get_index(
+        &self,
+        chunk: &Chunk,
+        target: &Value,
+        index: &Value,
+    ) -> Result<Value, InterpretError> {
+        match target {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjList(list),
+            }) => {
+                let idx = self.list_index(chunk, list, index)?;
+                Ok(list.get(idx).unwrap_or_default())
+            }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjTuple(tuple),
+            }) => {
+                let idx = self.sequence_index(chunk, tuple.length(), index)?;
+                Ok(tuple.get(idx).unwrap_or_default())
+            }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBytes(bytes),
+            }) => {
+                let idx = self.sequence_index(chunk, bytes.length(), index)?;
+                Ok(Value::Number(crate::RoxNumber(bytes.bytes()[idx] as f64)))
+            }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjString(string),
+            }) => {
+                let idx = self.sequence_index(chunk, string.char_count(), index)?;
+                let character = string.substring(idx, idx + 1).unwrap_or_else(|| {
+                    panic!("Checked index {} was outside of string {}", idx, string)
+                });
+                Ok(Value::Object(RoxObject::new(ObjectType::ObjString(
+                    character,
+                ))))
+            }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjDict(dict),
+            }) => {
+                let key = self.map_key(chunk, index)?;
+                match dict.get(&key) {
+                    Some(value) => Ok(value),
+                    None => Err(self.runtime_error(chunk, &format!("Undefined key '{}'.", key))),
+                }
+            }
+            _ => Err(self.runtime_error(chunk, &format!("Cannot index into value {}.", target))),
+        }
+    }
+
+    /// Slices a string or list from start up to but not including
+    /// end. A nil bound stands for the start or end of the sequence.
+    fn get_slice(
+        &self,
+        chunk: &Chunk,
+        target: &Value,
+        start: &Value,
+        end: &Value,
+    ) -> Result<Value, InterpretError> {
+        let length = match target {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjString(string),
+            }) => string.char_count(),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjList(list),
+            }) => list.length(),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjTuple(tuple),
+            }) => tuple.length(),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBytes(bytes),
+            }) => bytes.length(),
+            _ => return Err(self.runtime_error(chunk, &format!("Cannot slice value {}.", target))),
+        };
+
+        let start = match start {
+            Value::Nil => 0,
+            _ => self.slice_bound(chunk, length, start)?,
+        };
+        let end = match end {
+            Value::Nil => length,
+            _ => self.slice_bound(chunk, length, end)?,
+        };
+        if start > end {
+            let message = format!("Slice start {} is after its end {}.", start, end);
+            return Err(self.runtime_error(chunk, &message));
+        }
+
+        let object_type = match target {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjString(string),
+            }) => ObjectType::ObjString(string.substring(start, end).unwrap_or_else(|| {
+                panic!("Checked slice {}..{} was outside of {}", start, end, string)
+            })),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjList(list),
+            }) => ObjectType::ObjList(RoxList::new(list.values()[start..end].to_vec())),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjTuple(tuple),
+            }) => ObjectType::ObjTuple(RoxTuple::new(tuple.values()[start..end].to_vec())),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBytes(bytes),
+            }) => ObjectType::ObjBytes(RoxBytes::new(bytes.bytes()[start..end].to_vec())),
+            _ => unreachable!("Slice target was checked above"),
+        };
+        Ok(Value::Object(RoxObject::new(object_type)))
+    }
+
+    fn get_property(
+        &self,
+        chunk: &Chunk,
+        receiver: &Value,
+        name: &RoxString,
+    ) -> Result<Value, InterpretError> {
+        match receiver {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjDict(dict),
+            }) => match dict.get(name) {
+                Some(value) => Ok(value),
+                None => Err(self.runtime_error(chunk, &format!("Undefined property '{}'.", name))),
+            },
+            _ => Err(self.runtime_error(
+                chunk,
+                &format!("Cannot read property '{}' of {}.", name, receiver),
+            )),
+        }
+    }
+
+    fn set_index(
+        &self,
+        chunk: &Chunk,
+        target: &Value,
+        index: &Value,
+        value: &Value,
+    ) -> Result<(), InterpretError> {
+        match target {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjList(list),
+            }) => {
+                list.check_mutable()
+                    .map_err(|message| self.runtime_error(chunk, &message))?;
+                let idx = self.list_index(chunk, list, index)?;
+                list.set(idx, value.clone());
+                Ok(())
+            }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjDict(dict),
+            }) => {
+                dict.check_mutable()
+                    .map_err(|message| self.runtime_error(chunk, &message))?;
+                let key = self.map_key(chunk, index)?;
+                dict.set(&key, value);
+                Ok(())
             }
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjTuple(_),
+            }) => Err(self.runtime_error(chunk, "Cannot modify a tuple.")),
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjBytes(_),
+            }) => Err(self.runtime_error(chunk, "Cannot modify bytes.")),
+            _ => Err(self.runtime_error(chunk, &format!("Cannot index into value {}.", target))),
+        }
+    }
+
+    /// Checks that index is a whole number within the bounds of list.
+    fn list_index(
+        &self,
+        chunk: &Chunk,
+        list: &RoxList,
+        index: &Value,
+    ) -> Result<usize, InterpretError> {
+        self.sequence_index(chunk, list.length(), index)
+    }
+
+    /// Checks that index is a whole number within a sequence of length.
+    fn sequence_index(
+        &self,
+        chunk: &Chunk,
+        length: usize,
+        index: &Value,
+    ) -> Result<usize, InterpretError> {
+        let idx = self.whole_number(chunk, index)?;
+        if idx < length {
+            Ok(idx)
+        } else {
+            Err(self.runtime_error(
+                chunk,
+                &format!("Index {} out of bounds for length {}.", idx, length),
+            ))
+        }
+    }
+
+    /// Like `sequence_index`, but a slice bound may also be
+    /// equal to the length of the sequence.
+    fn slice_bound(
+        &self,
+        chunk: &Chunk,
+        length: usize,
+        bound: &Value,
+    ) -> Result<usize, InterpretError> {
+        let idx = self.whole_number(chunk, bound)?;
+        if idx <= length {
+            Ok(idx)
+        } else {
+            Err(self.runtime_error(
+                chunk,
+                &format!("Slice bound {} out of bounds for length {}.", idx, length),
+            ))
+        }
+    }
+
+    fn whole_number(&self, chunk: &Chunk, index: &Value) -> Result<usize, InterpretError> {
+        match index {
+            Value::Number(num) if num.fract() == 0.0 && **num >= 0.0 => Ok(**num as usize),
+            _ => Err(self.runtime_error(
+                chunk,
+                &format!("Index must be a non-negative integer, got {}.", index),
+            )),
+        }
+    }
+
+    fn map_key(&self, chunk: &Chunk, key: &Value) -> Result<RoxString, InterpretError> {
+        match key {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjString(key),
+            }) => Ok(key.clone()),
+            _ => Err(self.runtime_error(chunk, &format!("Map key must be a string, got {}.", key))),
         }
     }
 
+    /// Builds a runtime error tagged with the line of the
+    /// instruction currently being executed.
+    fn runtime_error(&self, chunk: &Chunk, message: &str) -> InterpretError {
+        let line = chunk.get_line(self.ip - 1);
+        InterpretError::RuntimeError(format!("[line {}]: {}", line, message))
+    }
+
     fn is_falsey(&self, value: Value) -> bool {
         matches!(value, Value::Boolean(false) | Value::Nil)
     }
@@ -283,19 +1003,133 @@ impl VM {
         lhs: &'a Value,
         rhs: &'a Value,
     ) -> (bool, Option<&'a RoxString>, Option<&'a RoxString>) {
-        match lhs {
-            Value::Object(obj_one) => match &obj_one.object_type {
-                ObjectType::ObjString(str_1) => match rhs {
-                    Value::Object(obj_two) => match &obj_two.object_type {
-                        ObjectType::ObjString(str_2) => (true, Some(str_1), Some(str_2)),
-                    },
+        match (lhs, rhs) {
+            (Value::Object(obj_one), Value::Object(obj_two)) => {
+                match (&obj_one.object_type, &obj_two.object_type) {
+                    (ObjectType::ObjString(str_1), ObjectType::ObjString(str_2)) => {
+                        (true, Some(str_1), Some(str_2))
+                    }
                     _ => (false, None, None),
-                },
-            },
+                }
+            }
             _ => (false, None, None),
         }
     }
 
+    /// Compares two numbers or two strings, pushing whether a
+    /// relates to b with the expected ordering.
+    fn relate(
+        &self,
+        chunk: &Chunk,
+        a: &Value,
+        b: &Value,
+        expected: Ordering,
+    ) -> Result<Value, InterpretError> {
+        let comparable = (a.is_numeric() && b.is_numeric()) || self.check_for_strings(a, b).0;
+
+        if !comparable {
+            let message = format!(
+                "Can only compare two numbers or two strings: a=({}) b=({})",
+                a, b
+            );
+            return Err(self.runtime_error(chunk, &message));
+        }
+
+        Ok(Value::Boolean(a.partial_cmp(b) == Some(expected)))
+    }
+
+    fn call_value(
+        &mut self,
+        chunk: &Chunk,
+        callee: &Value,
+        args: &[Value],
+    ) -> Result<Value, InterpretError> {
+        match callee {
+            Value::Object(RoxObject {
+                object_type: ObjectType::ObjNative(native),
+            }) => {
+                let result = native
+                    .call(&mut self.natives, args)
+                    .map_err(|message| self.runtime_error(chunk, &message))?;
+
+                match self.natives.take_eval() {
+                    Some(source) => self.eval(chunk, &source),
+                    None => Ok(result),
+                }
+            }
+            _ => {
+                Err(self.runtime_error(chunk, &format!("Can only call functions, got {}.", callee)))
+            }
+        }
+    }
+
+    /// Applies an arithmetic instruction to two numbers. The result is
+    /// a big integer when either operand is one, or when integer
+    /// promotion is enabled and whole numbers give a result too large
+    /// to be exact.
+    fn arithmetic(
+        &self,
+        chunk: &Chunk,
+        instruction: OpCode,
+        a: Value,
+        b: Value,
+    ) -> Result<Value, InterpretError> {
+        if a.as_big_int().is_some() || b.as_big_int().is_some() {
+            let (Some(a), Some(b)) = (VM::whole_big_int(&a), VM::whole_big_int(&b)) else {
+                let message = format!(
+                    "Big integers can only be combined with whole numbers: a=({}) b=({})",
+                    a, b
+                );
+                return Err(self.runtime_error(chunk, &message));
+            };
+            return self.big_int_arithmetic(chunk, instruction, &a, &b);
+        }
+
+        let (a, b) = self.check_for_non_number_types(chunk, a, b)?;
+        let result = match instruction {
+            OpCode::OpAdd => a.clone() + b.clone(),
+            OpCode::OpSubtract => a.clone() - b.clone(),
+            OpCode::OpMultiply => a.clone() * b.clone(),
+            _ => a.clone() / b.clone(),
+        };
+
+        let overflowed = matches!(&result, Value::Number(num) if num.abs() > MAX_SAFE_INTEGER);
+        if self.integer_promotion && overflowed && !matches!(instruction, OpCode::OpDivide) {
+            if let (Some(a), Some(b)) = (VM::whole_big_int(&a), VM::whole_big_int(&b)) {
+                return self.big_int_arithmetic(chunk, instruction, &a, &b);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Converts a big integer or a whole number to a big integer.
+    fn whole_big_int(value: &Value) -> Option<RoxBigInt> {
+        match value {
+            Value::Number(num) => RoxBigInt::from_f64(**num),
+            _ => value.as_big_int().cloned(),
+        }
+    }
+
+    fn big_int_arithmetic(
+        &self,
+        chunk: &Chunk,
+        instruction: OpCode,
+        a: &RoxBigInt,
+        b: &RoxBigInt,
+    ) -> Result<Value, InterpretError> {
+        let result = match instruction {
+            OpCode::OpAdd => a + b,
+            OpCode::OpSubtract => a - b,
+            OpCode::OpMultiply => a * b,
+            _ => a
+                .checked_div(b)
+                .ok_or_else(|| self.runtime_error(chunk, "Big integer division by zero."))?,
+        };
+
+        Ok(Value::Object(RoxObject::new(ObjectType::ObjBigInt(result))))
+    }
+
     fn check_for_non_number_types(
         &self,
         chunk: &Chunk,
@@ -326,9 +1160,57 @@ impl VM {
         Ok((a, b))
     }
 
+    /// Compiles and runs source in this VM for the `eval` native. The
+    /// evaluated code sees the caller's globals but not its locals or
+    /// match bindings, and its result is the value of a trailing
+    /// expression, or nil.
+    fn eval(&mut self, chunk: &Chunk, source: &str) -> Result<Value, InterpretError> {
+        let compiled = Compiler::compile_eval(
+            source,
+            &mut self.enums,
+            &mut self.global_types,
+            self.type_guards,
+        )
+        .map_err(|msg| self.runtime_error(chunk, &format!("eval failed: {}", msg)))?;
+        report_warnings(&compiled);
+
+        let ip = self.ip;
+        let stack_size = self.stack.size();
+        let bindings_size = self.bindings.len();
+        let base = self.stack.set_base(stack_size);
+        let bindings_base = std::mem::replace(&mut self.bindings_base, bindings_size);
+        self.ip = 0;
+
+        let result = self.execute(&compiled.chunk);
+
+        self.ip = ip;
+        self.stack.set_base(base);
+        self.bindings_base = bindings_base;
+        let value = if self.stack.size() > stack_size {
+            self.stack.pop().unwrap_or_default()
+        } else {
+            Value::Nil
+        };
+        while self.stack.size() > stack_size {
+            self.stack.pop();
+        }
+        self.bindings.truncate(bindings_size);
+
+        result.map(|_| value)
+    }
+
     pub fn interpret(&mut self, source: &str) -> InterpretResult {
-        let chunk = match Compiler::compile(source) {
-            Ok(chunk) => chunk,
+        let compiled = if self.type_guards {
+            Compiler::compile_with_type_guards(source, &mut self.enums, &mut self.global_types)
+        } else {
+            Compiler::compile(source, &mut self.enums, &mut self.global_types)
+        };
+
+        let chunk = match compiled {
+            Ok(compiled) => {
+                report_warnings(&compiled);
+                compiled.chunk
+            }
             Err(msg) => {
                 return Err(InterpretError::CompileError(format!(
                     "Compiler error in VM interpreter: {}",
@@ -342,10 +1224,18 @@ impl VM {
         }
 
         // run vm with chunk filled with compiled opcodes
+        // from its start, after any earlier interpreted chunk
+        self.ip = 0;
         self.run(&chunk)
     }
 }
 
+fn report_warnings(compiled: &Compiled) {
+    for warning in &compiled.warnings {
+        eprintln!("{}", warning);
+    }
+}
+
 impl Default for VM {
     fn default() -> Self {
         Self::new()
@@ -357,6 +1247,20 @@ mod tests {
     use super::*;
     use crate::error;
 
+    fn global(vm: &VM, name: &str) -> String {
+        vm.globals.get(&RoxString::new(name)).unwrap().to_string()
+    }
+
+    fn assert_runtime_error(source: &str) {
+        let mut vm = VM::new();
+        let result = vm.interpret(source);
+        assert!(
+            matches!(result, Err(InterpretError::RuntimeError(_))),
+            "{}",
+            source
+        );
+    }
+
     #[test]
     fn test_negate_op() {
         let mut vm = VM::new();
@@ -402,6 +1306,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compile_error_stops_the_script() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var ran = true; print ;");
+        assert!(matches!(result, Err(InterpretError::CompileError(_))));
+        assert!(vm.globals.get(&RoxString::new("ran")).is_none());
+    }
+
     #[test]
     fn test_while_loop_simple() {
         let mut vm = VM::new();
@@ -418,6 +1330,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_for_loop_pops_increment() {
+        let mut vm = VM::new();
+        if let Err(msg) = vm.interpret("for (var a = 0; a < 3; a = a + 1) { var b = a; }") {
+            panic!("{}", msg)
+        }
+        assert_eq!(vm.stack.size(), 0);
+    }
+
     #[test]
     fn test_for_loop_global() {
         let mut vm = VM::new();
@@ -437,4 +1358,804 @@ mod tests {
             panic!("{}", msg)
         }
     }
+
+    #[test]
+    fn test_enum_match_binds_payload() {
+        let mut vm = VM::new();
+        if let Err(msg) = vm.interpret(
+            "enum Color { Red, Green, Blue(r) }
+            var c = Color.Blue(7);
+            var x = match (c) { Color.Red => 1, Color.Green => 2, Color.Blue(r) => r + 1 };",
+        ) {
+            panic!("{}", msg)
+        }
+
+        let x = vm.globals.get(&RoxString::new("x")).unwrap();
+        assert_eq!(*x, Value::Number(crate::RoxNumber(8.0)));
+    }
+
+    #[test]
+    fn test_match_literals_and_ranges() {
+        let mut vm = VM::new();
+        if let Err(msg) = vm.interpret(
+            "var n = 3;
+            var x = match (n) { 0 => \"zero\", 1..5 => \"small\", default => \"big\" };",
+        ) {
+            panic!("{}", msg)
+        }
+
+        let x = vm.globals.get(&RoxString::new("x")).unwrap();
+        assert_eq!(
+            *x,
+            Value::Object(RoxObject::new(ObjectType::ObjString(RoxString::new(
+                "small"
+            ))))
+        );
+    }
+
+    #[test]
+    fn test_match_without_matching_arm() {
+        let mut vm = VM::new();
+        let result =
+            vm.interpret("enum Light { On, Off } var x = match (Light.Off) { Light.On => 1 };");
+
+        assert!(matches!(result, Err(InterpretError::RuntimeError(_))));
+    }
+
+    #[test]
+    fn test_list_destructuring_with_rest() {
+        let mut vm = VM::new();
+        if let Err(msg) = vm.interpret("var [a, b, ...rest] = [1, 2, 3, 4]; [a, b] = [b, a];") {
+            panic!("{}", msg)
+        }
+
+        let a = vm.globals.get(&RoxString::new("a")).unwrap();
+        let rest = vm.globals.get(&RoxString::new("rest")).unwrap();
+        assert_eq!(*a, Value::Number(crate::RoxNumber(2.0)));
+        assert_eq!(
+            rest.to_string(),
+            RoxList::new(vec![
+                Value::Number(crate::RoxNumber(3.0)),
+                Value::Number(crate::RoxNumber(4.0))
+            ])
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_map_destructuring_in_scope() {
+        let mut vm = VM::new();
+        if let Err(msg) = vm.interpret(
+            "var total; { var {width, height} = {width: 3, \"height\": 4}; total = width * height; }",
+        ) {
+            panic!("{}", msg)
+        }
+
+        let total = vm.globals.get(&RoxString::new("total")).unwrap();
+        assert_eq!(*total, Value::Number(crate::RoxNumber(12.0)));
+    }
+
+    #[test]
+    fn test_destructuring_shape_mismatch() {
+        let mut vm = VM::new();
+        let too_many = vm.interpret("var [a, b] = [1, 2, 3];");
+        assert!(matches!(too_many, Err(InterpretError::RuntimeError(_))));
+
+        let missing_key = vm.interpret("var {name} = {age: 1};");
+        assert!(matches!(missing_key, Err(InterpretError::RuntimeError(_))));
+    }
+
+    #[test]
+    fn test_type_annotation_mismatch_is_compile_error() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var a: Number = \"one\";");
+
+        assert!(matches!(result, Err(InterpretError::CompileError(_))));
+    }
+
+    #[test]
+    fn test_type_guards_check_values_at_runtime() {
+        let source = "var xs = [1, \"two\"]; var n: Number = xs[1];";
+
+        let mut unguarded = VM::new();
+        assert!(unguarded.interpret(source).is_ok());
+
+        let mut guarded = VM::new();
+        guarded.set_type_guards(true);
+        let result = guarded.interpret(source);
+        assert!(matches!(result, Err(InterpretError::RuntimeError(_))));
+    }
+
+    #[test]
+    fn test_type_guards_check_destructured_values() {
+        let source = "var x: Number = 1; var y; var pair = (\"str\", 2); [x, y] = pair;";
+
+        let mut unguarded = VM::new();
+        assert!(unguarded.interpret(source).is_ok());
+
+        let mut guarded = VM::new();
+        guarded.set_type_guards(true);
+        let result = guarded.interpret(source);
+        assert!(matches!(result, Err(InterpretError::RuntimeError(_))));
+    }
+
+    #[test]
+    fn test_optional_chaining_short_circuits_on_nil() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var config = nil; var city = config?.address.city; var first = config?[\"tags\"][0];",
+        );
+        assert!(result.is_ok());
+
+        let city = vm.globals.get(&RoxString::new("city")).unwrap();
+        let first = vm.globals.get(&RoxString::new("first")).unwrap();
+        assert_eq!(*city, Value::Nil);
+        assert_eq!(*first, Value::Nil);
+    }
+
+    #[test]
+    fn test_property_access_on_maps() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var m = {a: {b: 1}}; m.a.b = 2; var b = m?.a?.b;");
+        assert!(result.is_ok());
+
+        let b = vm.globals.get(&RoxString::new("b")).unwrap();
+        assert_eq!(*b, Value::Number(crate::RoxNumber(2.0)));
+
+        assert_runtime_error("var m = {a: 1}; var c = m.c;");
+    }
+
+    #[test]
+    fn test_string_ordering() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var lt = \"apple\" < \"banana\"; var ge = \"b\" >= \"c\";");
+        assert!(result.is_ok());
+
+        let lt = vm.globals.get(&RoxString::new("lt")).unwrap();
+        let ge = vm.globals.get(&RoxString::new("ge")).unwrap();
+        assert_eq!(*lt, Value::Boolean(true));
+        assert_eq!(*ge, Value::Boolean(false));
+
+        assert_runtime_error("var bad = 1 < \"a\";");
+    }
+
+    #[test]
+    fn test_sort_orders_mixed_values() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var sorted = sort([\"b\", 2, nil, \"a\", 1]);");
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "sorted"), "[nil, 1, 2, \"a\", \"b\"]");
+    }
+
+    #[test]
+    fn test_string_methods() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var parts = \" a,b \".trim().split(\",\"); var joined = \"+\".join(parts).upper();",
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "joined"), "\"A+B\"");
+
+        assert_runtime_error("var n = \"abc\".len(1);");
+    }
+
+    #[test]
+    fn test_string_slicing_counts_characters() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var s = \"naïve\"[1..3]; var c = \"naïve\"[2];");
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "s"), "\"aï\"");
+        assert_eq!(global(&vm, "c"), "\"ï\"");
+    }
+
+    #[test]
+    fn test_math_natives() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var hyp = sqrt(pow(3, 2) + pow(4, 2)); var big = max(INF, 1);");
+        assert!(result.is_ok());
+
+        let hyp = vm.globals.get(&RoxString::new("hyp")).unwrap();
+        let big = vm.globals.get(&RoxString::new("big")).unwrap();
+        assert_eq!(*hyp, Value::Number(crate::RoxNumber(5.0)));
+        assert_eq!(*big, Value::Number(crate::RoxNumber(f64::INFINITY)));
+
+        assert_runtime_error("var root = sqrt(\"four\");");
+    }
+
+    #[test]
+    fn test_numbers_are_double_precision() {
+        let mut vm = VM::new();
+        assert!(vm.interpret("var n = 16777217;").is_ok());
+
+        assert_eq!(global(&vm, "n"), "16777217");
+    }
+
+    #[test]
+    fn test_seeded_random_replays() {
+        let source = "var xs = [1, 2, 3, 4, 5]; shuffle(xs); var n = random();";
+
+        let mut first = VM::new();
+        first.seed_random(7);
+        assert!(first.interpret(source).is_ok());
+
+        let mut second = VM::new();
+        second.seed_random(7);
+        assert!(second.interpret(source).is_ok());
+
+        for name in ["xs", "n"] {
+            let name = RoxString::new(name);
+            assert_eq!(first.globals.get(&name), second.globals.get(&name));
+        }
+    }
+
+    fn sandbox_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("rox-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_file_natives_in_sandbox() {
+        let dir = sandbox_dir("files");
+        let mut vm = VM::new();
+        vm.set_sandbox_root(&dir).unwrap();
+
+        std::fs::write(dir.join("lines.txt"), "a\nb\n").unwrap();
+
+        let result = vm.interpret(
+            "write_file(\"data.txt\", \"a\"); append_file(\"data.txt\", \"b\");
+             var text = read_file(\"data.txt\"); var lines = read_lines(\"lines.txt\");
+             var names = list_dir(\".\"); remove_file(\"data.txt\");
+             var gone = !exists(\"data.txt\");",
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "text"), "\"ab\"");
+        assert_eq!(global(&vm, "lines"), "[\"a\", \"b\"]");
+        assert_eq!(global(&vm, "names"), "[\"data.txt\", \"lines.txt\"]");
+        assert_eq!(global(&vm, "gone"), "true");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_natives_cannot_escape_sandbox() {
+        let dir = sandbox_dir("escape");
+        let mut vm = VM::new();
+        vm.set_sandbox_root(&dir).unwrap();
+
+        let parent = vm.interpret("var text = read_file(\"../secret.txt\");");
+        assert!(matches!(parent, Err(InterpretError::RuntimeError(_))));
+
+        let mut vm = VM::new();
+        vm.set_sandbox_root(&dir).unwrap();
+        let absolute = vm.interpret("var found = exists(\"/etc/passwd\");");
+        assert!(matches!(absolute, Err(InterpretError::RuntimeError(_))));
+
+        let mut vm = VM::new();
+        vm.set_sandbox_root(&dir).unwrap();
+        let missing = vm.interpret("var text = read_file(\"missing.txt\");");
+        assert!(matches!(missing, Err(InterpretError::RuntimeError(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_natives_reject_dangling_symlinks() {
+        let dir = sandbox_dir("dangling");
+        let outside = std::env::temp_dir().join(format!("rox-outside-{}", std::process::id()));
+        std::os::unix::fs::symlink(&outside, dir.join("link")).unwrap();
+
+        for source in [
+            "write_file(\"link\", \"escaped\");",
+            "append_file(\"link\", \"escaped\");",
+            "write_file(\"link/inner.txt\", \"escaped\");",
+        ] {
+            let mut vm = VM::new();
+            vm.set_sandbox_root(&dir).unwrap();
+            let result = vm.interpret(source);
+            assert!(
+                matches!(result, Err(InterpretError::RuntimeError(_))),
+                "{}",
+                source
+            );
+        }
+        assert!(!outside.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_input_natives_read_from_buffer() {
+        let mut vm = VM::new();
+        vm.set_input(std::io::Cursor::new("Ada\r\nsecond\nrest\nof it"));
+
+        let result = vm.interpret(
+            "var name = input(\"\"); var line = read_line(); var rest = read_all(); var eof = read_line();",
+        );
+        assert!(result.is_ok());
+
+        let expected = [
+            ("name", "\"Ada\""),
+            ("line", "\"second\""),
+            ("rest", "\"rest\nof it\""),
+            ("eof", "nil"),
+        ];
+        for (name, value) in expected {
+            assert_eq!(global(&vm, name), value);
+        }
+    }
+
+    #[test]
+    fn test_script_args_and_exit() {
+        let mut vm = VM::new();
+        vm.set_script_args(vec![String::from("in.txt"), String::from("-v")]);
+
+        let result = vm.interpret("var first = args[0]; exit(2); var after = true;");
+        assert!(result.is_ok());
+        assert_eq!(vm.exit_code(), Some(2));
+
+        assert_eq!(global(&vm, "first"), "\"in.txt\"");
+        assert!(vm.globals.get(&RoxString::new("after")).is_none());
+    }
+
+    #[test]
+    fn test_json_parse_reports_line_and_column() {
+        let mut vm = VM::new();
+        vm.set_input(std::io::Cursor::new(
+            "{\"a\": [1, 2],\n \"b\": {\"c\": \"\\u00e9\"}}",
+        ));
+        let result = vm.interpret("var parsed = json_parse(read_all()); var c = parsed.b.c;");
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "c"), "\"é\"");
+
+        let mut vm = VM::new();
+        vm.set_input(std::io::Cursor::new("[1,\n  oops]"));
+        match vm.interpret("var parsed = json_parse(read_all());") {
+            Err(InterpretError::RuntimeError(message)) => assert!(message.contains("[2, 3]")),
+            _ => panic!("Expected a json_parse error"),
+        }
+    }
+
+    #[test]
+    fn test_json_stringify_detects_cycles() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var m = {a: 1}; m.self = m; var text = json_stringify(m, 0);");
+        assert!(matches!(result, Err(InterpretError::RuntimeError(_))));
+
+        // a value shared by siblings is not a cycle
+        let mut vm = VM::new();
+        let result = vm.interpret("var xs = [1]; var text = json_stringify({a: xs, b: xs}, nil);");
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "text"), "\"{\"a\":[1],\"b\":[1]}\"");
+    }
+
+    #[test]
+    fn test_time_round_trips_through_iso_8601() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var t = parse_time(\"1999-12-31T23:59:59-01:00\");
+             var utc = format_time(t.timestamp, nil); var year = time_parts(t.timestamp, 0).year;",
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(global(&vm, "utc"), "\"2000-01-01T00:59:59Z\"");
+        assert_eq!(global(&vm, "year"), "2000");
+
+        assert_runtime_error("var t = parse_time(\"2024-13-01\");");
+    }
+
+    #[test]
+    fn test_assert_fails_with_line() {
+        let mut vm = VM::new();
+        match vm.interpret("var x = 1;\nassert x == 2, \"x should be two\";") {
+            Err(InterpretError::RuntimeError(message)) => {
+                assert_eq!(message, "[line 2]: Assertion failed: x should be two")
+            }
+            _ => panic!("Expected a failed assertion"),
+        }
+    }
+
+    #[test]
+    fn test_test_blocks_only_run_in_test_mode() {
+        let source = "var ran = false;
+            test \"passes\" { ran = true; }
+            test \"fails\" { var local = 1; assert local == 2; }
+            var after = true;";
+
+        let mut vm = VM::new();
+        assert!(vm.interpret(source).is_ok());
+        assert_eq!(
+            *vm.globals.get(&RoxString::new("ran")).unwrap(),
+            Value::Boolean(false)
+        );
+        assert!(vm.test_results().is_empty());
+
+        let mut vm = VM::new();
+        vm.set_test_mode(true);
+        assert!(vm.interpret(source).is_ok());
+        assert_eq!(
+            *vm.globals.get(&RoxString::new("after")).unwrap(),
+            Value::Boolean(true)
+        );
+
+        let results = vm.test_results();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].failure, None);
+        assert_eq!(results[1].name, "fails");
+        assert!(results[1].failure.is_some());
+    }
+
+    #[test]
+    fn test_reflection_natives() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var m = {b: 1}; set_field(m, \"a\", [true]);
+             var keys = fields(m); var kind = type_of(get_field(m, \"a\")); var n = arity(sort);",
+        );
+        assert!(result.is_ok());
+
+        let expected = [("keys", "[\"a\", \"b\"]"), ("kind", "\"List\""), ("n", "1")];
+        for (name, value) in expected {
+            assert_eq!(global(&vm, name), value);
+        }
+
+        assert_runtime_error("var v = get_field({}, \"nope\");");
+    }
+
+    #[test]
+    fn test_eval_runs_against_globals() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var x = 2; var y = eval(\"x = x + 1; x * 10\");
+             var z; { var a = 5; z = eval(\"{ var b = 4; x = b; } x + 1;\") + a; }
+             var none = eval(\"var w = 1;\");",
+        );
+        assert!(result.is_ok());
+
+        let expected = [
+            ("x", "4"),
+            ("y", "30"),
+            ("z", "10"),
+            ("none", "nil"),
+            ("w", "1"),
+        ];
+        for (name, value) in expected {
+            assert_eq!(global(&vm, name), value);
+        }
+    }
+
+    #[test]
+    fn test_eval_compile_error_is_runtime_error() {
+        let mut vm = VM::new();
+        let result = vm.interpret("var x = eval(\"1 +\");");
+        assert!(
+            matches!(result, Err(InterpretError::RuntimeError(msg)) if msg.contains("eval failed"))
+        );
+
+        let mut vm = VM::new();
+        vm.set_test_mode(true);
+        let result = vm.interpret("test \"bad eval\" { eval(\"print;\"); } var after = true;");
+        assert!(result.is_ok());
+        assert!(vm.test_results()[0].failure.is_some());
+        assert!(vm.globals.get(&RoxString::new("after")).is_some());
+    }
+
+    #[test]
+    fn test_integer_promotion_is_opt_in() {
+        let source = "var x = 9007199254740993 * 1; var y = 4000000000000000 * 4000000000000000;
+                      var z = 0.5 * 40000000000000000000;";
+
+        let mut vm = VM::new();
+        assert!(vm.interpret(source).is_ok());
+        let y = vm.globals.get(&RoxString::new("y")).unwrap().clone();
+        assert!(y.as_big_int().is_none());
+
+        let mut vm = VM::new();
+        vm.set_integer_promotion(true);
+        assert!(vm.interpret(source).is_ok());
+        let expected = [
+            ("y", "16000000000000000000000000000000"),
+            ("z", "20000000000000000000"),
+        ];
+        for (name, value) in expected {
+            assert_eq!(global(&vm, name), value);
+        }
+        let y = vm.globals.get(&RoxString::new("y")).unwrap().clone();
+        assert!(y.as_big_int().is_some());
+        let z = vm.globals.get(&RoxString::new("z")).unwrap().clone();
+        assert!(z.as_big_int().is_none());
+    }
+
+    #[test]
+    fn test_big_int_errors() {
+        for source in [
+            "var x = 1n + 0.5;",
+            "var x = 1n / 0;",
+            "var x = 1n + \"a\";",
+        ] {
+            assert_runtime_error(source);
+        }
+    }
+
+    #[test]
+    fn test_set_deduplicates_equal_values() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var s = set([1, 1n, 1.0, 2.5, freeze([1]), freeze([1]), freeze({a: 1})]); var n = s.len(); var has = s.has(1n);",
+        );
+        assert!(result.is_ok());
+
+        let expected = [("n", "4"), ("has", "true")];
+        for (name, value) in expected {
+            assert_eq!(global(&vm, name), value);
+        }
+
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "var l = freeze([1]); var s = set([l, (l, 2)]); assert s.has([1]); assert s.has((freeze([1]), 2));
+             var zeros = set([0, -0]); assert zeros.len() == 1; var zero = zeros.values()[0];
+             var cycle = [1]; cycle[0] = cycle; freeze(cycle); var cycles = set([cycle]); assert cycles.has(cycle);",
+        );
+        assert!(result.is_ok());
+        assert_eq!(global(&vm, "zero"), "0");
+
+        let rejected = [
+            "var s = set([]); s.add(s);",
+            "var s = set([]); var l = [s]; s.add(l);",
+            "var l = [1]; var s = set([l]);",
+            "var s = set([{a: 1}]);",
+            "var s = set([(1, [2])]);",
+            "var inner = set([]); var s = set([inner]);",
+        ];
+        for source in rejected {
+            assert_runtime_error(source);
+        }
+    }
+
+    #[test]
+    fn test_frozen_values_reject_mutation() {
+        let prelude =
+            "var m = {list: [1], inner: {a: 1}, seen: set([])}; var alias = m; freeze(m);";
+        let mutations = [
+            "m.name = 1;",
+            "alias[\"name\"] = 1;",
+            "m.list[0] = 2;",
+            "m.inner.a = 2;",
+            "set_field(m, \"a\", 1);",
+            "shuffle(m.list);",
+            "m.seen.add(1);",
+            "var t = (1, 2); t[0] = 3;",
+        ];
+
+        for mutation in mutations {
+            assert_runtime_error(&format!("{} {}", prelude, mutation));
+        }
+
+        let mut vm = VM::new();
+        let result = vm.interpret("var copy = {a: 1}; freeze({b: copy}); var a = copy.a;");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_bytes_errors() {
+        let mut vm = VM::new();
+        assert!(vm
+            .interpret("var b = 2; var bb = b\"\"; var n = b * 2;")
+            .is_ok());
+
+        let failures = [
+            "var x = from_hex(\"ff\").decode();",
+            "var x = bytes([256]);",
+            "var x = b\"ab\"; x[0] = 1;",
+            "var x = b\"ab\"[2];",
+            "var x = from_base64(\"@@\");",
+        ];
+        for source in failures {
+            assert_runtime_error(source);
+        }
+
+        for source in ["var x = b\"\\q\";", "var x = b\"\\x4\";", "var x = b\"é\";"] {
+            let mut vm = VM::new();
+            let result = vm.interpret(source);
+            assert!(
+                matches!(&result, Err(InterpretError::CompileError(msg)) if msg.contains("bytes literal") || msg.contains("ASCII")),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_loop_jump_errors() {
+        let mut vm = VM::new();
+        assert!(vm
+            .interpret(
+                "{ var a = 1; l: while (true) { var b = a; do { break l; } while (true); } }"
+            )
+            .is_ok());
+
+        let failures = [
+            "break;",
+            "{ continue; }",
+            "while (false) { break missing; }",
+            "a: while (false) { a: while (false) {} }",
+            "a: print 1;",
+            "do print 1; while (false)",
+        ];
+        for source in failures {
+            let mut vm = VM::new();
+            let result = vm.interpret(source);
+            assert!(
+                matches!(result, Err(InterpretError::CompileError(_))),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpret_twice_keeps_globals() {
+        let mut vm = VM::new();
+        assert!(vm.interpret("var count = 1;").is_ok());
+        assert!(vm
+            .interpret("count = count + 1; assert count == 2;")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_enums_persist_between_compilations() {
+        let mut vm = VM::new();
+        assert!(vm.interpret("enum Shape { Point, Circle(r) }").is_ok());
+        assert!(vm
+            .interpret("var area = match (Shape.Circle(2)) { Shape.Circle(r) => r * r, default => 0 }; assert area == 4;")
+            .is_ok());
+        assert!(vm
+            .interpret("enum Light { On, Off } var l = eval(\"Light.On\"); assert l == Light.On;")
+            .is_ok());
+        assert!(vm.interpret("eval(\"enum Later { Now }\");").is_ok());
+        assert!(vm.interpret("var n = Later.Now;").is_ok());
+
+        vm.reset();
+        assert!(matches!(
+            vm.interpret("var p = Shape.Point;"),
+            Err(InterpretError::RuntimeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_annotations_persist_between_compilations() {
+        let mut vm = VM::new();
+        assert!(vm.interpret("var n: Number = 1;").is_ok());
+        assert!(matches!(
+            vm.interpret("n = \"one\";"),
+            Err(InterpretError::CompileError(_))
+        ));
+        assert!(matches!(
+            vm.interpret("eval(\"n = true;\");"),
+            Err(InterpretError::RuntimeError(_))
+        ));
+
+        let mut vm = VM::new();
+        vm.set_type_guards(true);
+        assert!(vm.interpret("var n: Number = 1; var xs = [\"a\"];").is_ok());
+        assert!(matches!(
+            vm.interpret("n = xs[0];"),
+            Err(InterpretError::RuntimeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cyclic_values_print_and_compare() {
+        let list = RoxList::new(vec![Value::Nil]);
+        let value = Value::Object(RoxObject::new(ObjectType::ObjList(list.clone())));
+        list.set(0, value.clone());
+        assert_eq!(value.to_string(), "[[...]]");
+
+        let other = RoxList::new(vec![Value::Nil]);
+        let other_value = Value::Object(RoxObject::new(ObjectType::ObjList(other.clone())));
+        other.set(0, other_value.clone());
+        assert_eq!(value.total_cmp(&other_value), Ordering::Equal);
+        assert_eq!(value, other_value);
+    }
+
+    #[test]
+    fn test_string_repeat_and_replace_limit_length() {
+        let mut vm = VM::new();
+        assert!(vm
+            .interpret(
+                "assert \"ab\".repeat(3) == \"ababab\"; assert \"\".repeat(pow(2, 63)) == \"\";
+                 assert \"abc\".replace(\"\", \"-\") == \"-a-b-c-\"; assert \"aXa\".replace(\"a\", \"\") == \"X\";"
+            )
+            .is_ok());
+
+        for source in [
+            "var s = \"ab\".repeat(pow(2, 63));",
+            "var s = \"ab\".repeat(pow(2, 30));",
+            "var s = \"ab\".repeat(pow(2, 15)); var t = s.replace(\"\", s);",
+            "var s = \"ab\".repeat(pow(2, 15)); var t = s.replace(\"a\", s);",
+        ] {
+            assert_runtime_error(source);
+        }
+    }
+
+    #[test]
+    fn test_random_int_rejects_huge_spans() {
+        let mut vm = VM::new();
+        assert!(vm
+            .interpret("var n = random_int(0, pow(2, 53) - 1); var m = random_int(-1, -1); assert m == -1;")
+            .is_ok());
+
+        for source in [
+            "var n = random_int(0, pow(2, 64));",
+            "var n = random_int(0, pow(2, 53));",
+            "var n = random_int(-pow(10, 300), pow(10, 300));",
+            "var n = random_int(0, pow(10, 400));",
+        ] {
+            assert_runtime_error(source);
+        }
+    }
+
+    #[test]
+    fn test_json_indent_is_optional_and_nesting_is_limited() {
+        let mut vm = VM::new();
+        assert!(vm
+            .interpret(
+                "assert json_stringify([1, [nil]]) == \"[1,[null]]\";
+                 var deep = json_parse(\"[\".repeat(512) + \"]\".repeat(512));
+                 var nested = [];
+                 for (var i = 1; i < 512; i = i + 1) { nested = [nested]; }
+                 var text = json_stringify(nested);"
+            )
+            .is_ok());
+
+        for source in [
+            "var v = json_parse(\"[\".repeat(100000));",
+            "var v = json_parse(\"[\".repeat(513) + \"]\".repeat(513));",
+            "var v = json_stringify(json_parse(\"[\".repeat(512) + \"]\".repeat(512)), 2, 3);",
+            "var nested = []; for (var i = 0; i < 512; i = i + 1) { nested = [nested]; } var t = json_stringify(nested);",
+        ] {
+            assert_runtime_error(source);
+        }
+    }
+
+    #[test]
+    fn test_time_natives_reject_out_of_range_input() {
+        let mut vm = VM::new();
+        assert!(vm
+            .interpret("var p = time_parts(0, 0); assert p.year == 1970; sleep(0);")
+            .is_ok());
+
+        for source in [
+            "var p = time_parts(pow(10, 300), 0);",
+            "var p = time_parts(sqrt(-1), 0);",
+            "var p = time_parts(-pow(10, 300), 0);",
+            "var t = format_time(pow(10, 400), 0);",
+            "var t = format_time(sqrt(-1), 60);",
+            "sleep(pow(10, 300));",
+        ] {
+            assert_runtime_error(source);
+        }
+    }
+
+    #[test]
+    fn test_eval_has_its_own_match_bindings() {
+        let mut vm = VM::new();
+        let result = vm.interpret(
+            "enum E { B(x) }
+             var inner = match (E.B(1)) {
+                 E.B(x) => eval(\"enum F { G(y) } match (F.G(2)) { F.G(y) => y }\")
+             };
+             assert inner == 2;
+             var outer = match (E.B(3)) { E.B(x) => [eval(\"1\"), x] };
+             assert outer[1] == 3;",
+        );
+        assert!(result.is_ok());
+    }
 }
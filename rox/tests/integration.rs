@@ -43,3 +43,38 @@ make_rox_test!(test_if_else_statement, "rox_tests/if_else_statement.rox");
 make_rox_test!(test_if_statement_scope, "rox_tests/if_statement_scope.rox");
 make_rox_test!(test_logical_expr, "rox_tests/logical_expr.rox");
 make_rox_test!(test_while_loop, "rox_tests/while_loop.rox");
+make_rox_test!(test_enum_match, "rox_tests/enum_match.rox");
+make_rox_test!(test_destructuring, "rox_tests/destructuring.rox");
+make_rox_test!(test_type_annotations, "rox_tests/type_annotations.rox");
+make_rox_test!(test_optional_chaining, "rox_tests/optional_chaining.rox");
+make_rox_test!(test_string_comparison, "rox_tests/string_comparison.rox");
+make_rox_test!(test_string_methods, "rox_tests/string_methods.rox");
+make_rox_test!(test_math, "rox_tests/math.rox");
+make_rox_test!(test_random, "rox_tests/random.rox");
+make_rox_test!(test_file_io, "rox_tests/file_io.rox");
+make_rox_test!(test_process, "rox_tests/process.rox");
+make_rox_test!(test_json, "rox_tests/json.rox");
+make_rox_test!(test_date_time, "rox_tests/date_time.rox");
+make_rox_test!(test_assert_tests, "rox_tests/assert_tests.rox");
+make_rox_test!(test_reflection, "rox_tests/reflection.rox");
+make_rox_test!(test_eval, "rox_tests/eval.rox");
+make_rox_test!(test_big_int, "rox_tests/big_int.rox");
+make_rox_test!(test_sets, "rox_tests/sets.rox");
+make_rox_test!(test_tuples_and_freeze, "rox_tests/tuples_and_freeze.rox");
+make_rox_test!(test_bytes, "rox_tests/bytes.rox");
+make_rox_test!(test_loops, "rox_tests/loops.rox");
+make_rox_test!(test_cycles, "rox_tests/cycles.rox");
+
+#[test]
+fn test_promote_ints_flag() {
+    let flags = ["rox", "--promote-ints"].map(String::from);
+    let mut config = Config::new(&mut flags.into_iter()).unwrap();
+    if let Err(msg) = config.run_file_with_filename("rox_tests/int_promotion.rox") {
+        panic!("{}", msg)
+    }
+
+    let mut config = Config::new(&mut ["rox"].map(String::from).into_iter()).unwrap();
+    assert!(config
+        .run_file_with_filename("rox_tests/int_promotion.rox")
+        .is_err());
+}